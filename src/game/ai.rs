@@ -0,0 +1,288 @@
+use crate::game::colony::StructureKind;
+use crate::game::economy::{structure_cost, COLONY_COST, MAX_MINES_PER_COLONY};
+use crate::game::fleet::Fleet;
+use crate::game::hazard::{accumulate_risk, HazardKind};
+use crate::game::knowledge::{BodyIntel, Knowledge};
+use crate::game::order::Order;
+use crate::game::player::PlayerId;
+use crate::game::system::{OrbitalBody, StarSystem};
+use crate::game::Game;
+use std::collections::HashSet;
+
+/// Failure chance assumed for bodies nobody has probed yet.
+const UNKNOWN_PROBE_FAILURE: f64 = 0.3;
+/// Probes aren't wasted on bodies likelier than this to eat them.
+const MAX_PROBE_RISK: f64 = 0.5;
+
+/// A computer opponent. It is asked for orders once per turn, before the
+/// tick, and may only act through orders the game validates like anyone
+/// else's.
+pub trait AiPlayer {
+    fn name(&self) -> &str;
+    fn issue_orders(&mut self, game: &Game, player: PlayerId) -> Vec<Order>;
+}
+
+/// Never issues orders; a baseline for the harness.
+pub struct IdleAi;
+
+impl AiPlayer for IdleAi {
+    fn name(&self) -> &str {
+        "idle"
+    }
+
+    fn issue_orders(&mut self, _game: &Game, _player: PlayerId) -> Vec<Order> {
+        Vec::new()
+    }
+}
+
+/// Probes the safest unknown bodies nearby, settles the richest surveyed
+/// ones, and steers clear of systems it has seen pirates in.
+#[derive(Clone, Default)]
+pub struct ExplorerAi {
+    visited: HashSet<u32>,
+}
+
+fn bodies(system: &StarSystem) -> impl Iterator<Item = &OrbitalBody> {
+    system
+        .orbitals
+        .iter()
+        .flat_map(|body| std::iter::once(body).chain(body.moons.iter()))
+}
+
+fn expected_probe_failure(knowledge: &Knowledge, body: &OrbitalBody) -> f64 {
+    match knowledge.body_intel(body.id) {
+        Some(BodyIntel::Estimated { probe_failure, .. }) => probe_failure,
+        _ => UNKNOWN_PROBE_FAILURE,
+    }
+}
+
+fn known_pirates(knowledge: &Knowledge, system: &StarSystem) -> bool {
+    bodies(system).any(|body| {
+        knowledge.is_surveyed(body.id) && body.hazards.iter().any(|h| h.kind == HazardKind::Pirates)
+    })
+}
+
+fn expected_yield(body: &OrbitalBody) -> f64 {
+    body.yield_rate as f64 * accumulate_risk(&body.hazards).multiplier
+}
+
+impl ExplorerAi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn fleet_orders(
+        &mut self,
+        game: &Game,
+        knowledge: &Knowledge,
+        fleet: &Fleet,
+        credits: &mut f64,
+    ) -> Vec<Order> {
+        let Some(system) = game.universe().system(fleet.system) else {
+            return Vec::new();
+        };
+        self.visited.insert(system.id);
+
+        let settle = bodies(system)
+            .filter(|b| knowledge.is_surveyed(b.id) && game.colony(b.id).is_none())
+            .max_by(|a, b| expected_yield(a).total_cmp(&expected_yield(b)));
+        if let Some(body) = settle {
+            if *credits >= COLONY_COST {
+                *credits -= COLONY_COST;
+                return vec![Order::Colonise {
+                    fleet: fleet.id,
+                    body: body.id,
+                }];
+            }
+        }
+
+        let target = bodies(system)
+            .filter(|b| !knowledge.is_surveyed(b.id))
+            .map(|b| (b, expected_probe_failure(knowledge, b)))
+            .filter(|(_, risk)| *risk <= MAX_PROBE_RISK)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let (Some((body, _)), true) = (target, fleet.probes > 0) {
+            return vec![Order::LaunchProbe {
+                fleet: fleet.id,
+                body: body.id,
+            }];
+        }
+
+        // Nothing left to do here: head for the least explored safe neighbour.
+        let next = system
+            .links
+            .iter()
+            .copied()
+            .filter(|&id| {
+                game.universe()
+                    .system(id)
+                    .is_some_and(|sys| !known_pirates(knowledge, sys))
+            })
+            .min_by_key(|id| (self.visited.contains(id), *id));
+        match next {
+            Some(to) => vec![Order::MoveFleet {
+                fleet: fleet.id,
+                to,
+            }],
+            None => Vec::new(),
+        }
+    }
+}
+
+impl AiPlayer for ExplorerAi {
+    fn name(&self) -> &str {
+        "explorer"
+    }
+
+    fn issue_orders(&mut self, game: &Game, player: PlayerId) -> Vec<Order> {
+        let Some(me) = game.player(player) else {
+            return Vec::new();
+        };
+        let mut credits = me.stockpile.credits;
+        let mut orders = Vec::new();
+
+        for colony in game.colonies().iter().filter(|c| c.owner == player) {
+            let mines = colony
+                .structures
+                .iter()
+                .filter(|s| **s == StructureKind::Mine)
+                .count();
+            let structure = if !colony.structures.contains(&StructureKind::Outpost) {
+                StructureKind::Outpost
+            } else if mines < MAX_MINES_PER_COLONY {
+                StructureKind::Mine
+            } else {
+                continue;
+            };
+            if credits >= structure_cost(structure) {
+                credits -= structure_cost(structure);
+                orders.push(Order::Build {
+                    body: colony.body,
+                    structure,
+                });
+            }
+        }
+
+        let fleets: Vec<&Fleet> = game
+            .fleets()
+            .iter()
+            .filter(|f| f.owner == player && f.is_docked() && f.route.is_empty())
+            .collect();
+        for fleet in fleets {
+            orders.extend(self.fleet_orders(game, &me.knowledge, fleet, &mut credits));
+        }
+        orders
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub player: PlayerId,
+    pub ai: String,
+    pub credits: f64,
+    pub colonies: usize,
+    pub surveyed_bodies: usize,
+    pub known_systems: usize,
+    pub fleets: usize,
+    pub rejected_orders: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchOutcome {
+    pub turns: u32,
+    pub winner: Option<PlayerId>,
+    pub standings: Vec<Standing>,
+}
+
+/// Runs a headless game between `ais`, one faction each, for `turns` ticks
+/// or until someone wins.
+pub fn run_match(seed: u64, mut ais: Vec<Box<dyn AiPlayer>>, turns: u32) -> MatchOutcome {
+    let mut game = Game::with_factions(seed, ais.len());
+    let mut rejected = vec![0u32; ais.len()];
+
+    for _ in 0..turns {
+        if game.outcome().is_some() {
+            break;
+        }
+        for (idx, ai) in ais.iter_mut().enumerate() {
+            let player = idx as PlayerId;
+            for order in ai.issue_orders(&game, player) {
+                if game.submit(player, order).is_err() {
+                    rejected[idx] += 1;
+                }
+            }
+        }
+        game.tick();
+    }
+
+    let standings = game
+        .players()
+        .iter()
+        .zip(&ais)
+        .map(|(player, ai)| Standing {
+            player: player.id,
+            ai: ai.name().to_string(),
+            credits: player.stockpile.credits,
+            colonies: game
+                .colonies()
+                .iter()
+                .filter(|c| c.owner == player.id)
+                .count(),
+            surveyed_bodies: player.knowledge.surveyed_bodies(),
+            known_systems: player.knowledge.known_systems(),
+            fleets: game
+                .fleets()
+                .iter()
+                .filter(|f| f.owner == player.id)
+                .count(),
+            rejected_orders: rejected[player.id as usize],
+        })
+        .collect();
+
+    MatchOutcome {
+        turns: game.turn(),
+        winner: game.outcome().map(|v| v.player),
+        standings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explorers_vs_idle(seed: u64) -> MatchOutcome {
+        run_match(
+            seed,
+            vec![
+                Box::new(ExplorerAi::new()),
+                Box::new(IdleAi),
+                Box::new(ExplorerAi::new()),
+            ],
+            40,
+        )
+    }
+
+    #[test]
+    fn matches_are_deterministic_per_seed() {
+        assert_eq!(explorers_vs_idle(17), explorers_vs_idle(17));
+    }
+
+    #[test]
+    fn explorers_outgrow_idle_players() {
+        for seed in [3, 17, 89] {
+            let outcome = explorers_vs_idle(seed);
+            assert_eq!(outcome.turns, 40);
+            let idle = &outcome.standings[1];
+            assert_eq!(idle.surveyed_bodies, 0);
+            assert_eq!(idle.colonies, 0);
+
+            for explorer in [&outcome.standings[0], &outcome.standings[2]] {
+                assert_eq!(explorer.ai, "explorer");
+                assert!(explorer.surveyed_bodies > 0, "seed {seed}: {explorer:?}");
+                assert!(explorer.colonies > 0, "seed {seed}: {explorer:?}");
+                assert!(explorer.credits > idle.credits, "seed {seed}: {explorer:?}");
+                assert_eq!(explorer.rejected_orders, 0, "seed {seed}: {explorer:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,574 @@
+//! JSON with every object's keys in sorted order, written straight from
+//! `Serialize` impls. It is byte for byte what `serde_json::to_value`
+//! followed by `to_string` prints, without building the `Value` tree, so
+//! state checksums taken either way agree.
+
+use serde::ser::{self, Error as _, Impossible, Serialize};
+use serde_json::Error;
+use std::collections::BTreeMap;
+
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    value.serialize(Canonical(&mut out))?;
+    Ok(out)
+}
+
+fn write_json<T: Serialize + ?Sized>(out: &mut Vec<u8>, value: &T) -> Result<(), Error> {
+    serde_json::to_writer(out, value)
+}
+
+struct Canonical<'a>(&'a mut Vec<u8>);
+
+impl<'a> Canonical<'a> {
+    fn open_variant(self, variant: &str) -> Result<&'a mut Vec<u8>, Error> {
+        self.0.push(b'{');
+        write_json(self.0, variant)?;
+        self.0.push(b':');
+        Ok(self.0)
+    }
+}
+
+impl<'a> ser::Serializer for Canonical<'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Elements<'a>;
+    type SerializeTuple = Elements<'a>;
+    type SerializeTupleStruct = Elements<'a>;
+    type SerializeTupleVariant = Elements<'a>;
+    type SerializeMap = Entries<'a>;
+    type SerializeStruct = Entries<'a>;
+    type SerializeStructVariant = Entries<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    // A `Value` keeps every float as an f64.
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        write_json(self.0, &(v as f64))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        write_json(self.0, &v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        write_json(self.0, v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        write_json(self.0, v)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.0.extend_from_slice(b"null");
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let out = self.open_variant(variant)?;
+        value.serialize(Canonical(&mut *out))?;
+        out.push(b'}');
+        Ok(())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Elements<'a>, Error> {
+        self.0.push(b'[');
+        Ok(Elements {
+            out: self.0,
+            first: true,
+            close: "]",
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Elements<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Elements<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Elements<'a>, Error> {
+        let out = self.open_variant(variant)?;
+        out.push(b'[');
+        Ok(Elements {
+            out,
+            first: true,
+            close: "]}",
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Entries<'a>, Error> {
+        Ok(Entries {
+            out: self.0,
+            entries: BTreeMap::new(),
+            key: None,
+            close: "}",
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Entries<'a>, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Entries<'a>, Error> {
+        let out = self.open_variant(variant)?;
+        Ok(Entries {
+            out,
+            entries: BTreeMap::new(),
+            key: None,
+            close: "}}",
+        })
+    }
+}
+
+/// Arrays keep their order, so elements are written as they come.
+struct Elements<'a> {
+    out: &'a mut Vec<u8>,
+    first: bool,
+    close: &'static str,
+}
+
+impl Elements<'_> {
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        if !self.first {
+            self.out.push(b',');
+        }
+        self.first = false;
+        value.serialize(Canonical(&mut *self.out))
+    }
+
+    fn close(self) -> Result<(), Error> {
+        self.out.extend_from_slice(self.close.as_bytes());
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for Elements<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl ser::SerializeTuple for Elements<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl ser::SerializeTupleStruct for Elements<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl ser::SerializeTupleVariant for Elements<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+/// Objects are held back until they end so their keys can go out sorted.
+/// A repeated key keeps its last value, as it would in a `Value`.
+struct Entries<'a> {
+    out: &'a mut Vec<u8>,
+    entries: BTreeMap<String, Vec<u8>>,
+    key: Option<String>,
+    close: &'static str,
+}
+
+impl Entries<'_> {
+    fn entry<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+        let mut bytes = Vec::new();
+        value.serialize(Canonical(&mut bytes))?;
+        self.entries.insert(key, bytes);
+        Ok(())
+    }
+
+    fn close(self) -> Result<(), Error> {
+        self.out.push(b'{');
+        for (i, (key, value)) in self.entries.iter().enumerate() {
+            if i > 0 {
+                self.out.push(b',');
+            }
+            write_json(self.out, key)?;
+            self.out.push(b':');
+            self.out.extend_from_slice(value);
+        }
+        self.out.extend_from_slice(self.close.as_bytes());
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for Entries<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(KeyText)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::custom("map value without a key"))?;
+        self.entry(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl ser::SerializeStruct for Entries<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.entry(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl ser::SerializeStructVariant for Entries<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.entry(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+/// A map key as the string a `Value` would store it under.
+struct KeyText;
+
+fn key_must_be_a_string() -> Error {
+    Error::custom("key must be a string")
+}
+
+impl ser::Serializer for KeyText {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ai::{AiPlayer, ExplorerAi};
+    use crate::game::player::PlayerId;
+    use crate::game::Game;
+
+    #[test]
+    fn matches_what_a_value_would_print() {
+        let mut game = Game::with_factions(31, 3);
+        let mut ais = [ExplorerAi::new(), ExplorerAi::new(), ExplorerAi::new()];
+        for _ in 0..40 {
+            for (idx, ai) in ais.iter_mut().enumerate() {
+                for order in ai.issue_orders(&game, idx as PlayerId) {
+                    let _ = game.submit(idx as PlayerId, order);
+                }
+            }
+            game.tick();
+        }
+        let value = serde_json::to_value(&game).unwrap();
+        assert_eq!(
+            String::from_utf8(to_vec(&game).unwrap()).unwrap(),
+            value.to_string()
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use crate::game::player::PlayerId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StructureKind {
+    Outpost,
+    Mine,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Colony {
+    pub body: u32,
+    pub owner: PlayerId,
+    pub founded: u32,
+    pub structures: Vec<StructureKind>,
+}
+
+impl Colony {
+    pub fn new(body: u32, owner: PlayerId, founded: u32) -> Self {
+        Self {
+            body,
+            owner,
+            founded,
+            structures: Vec::new(),
+        }
+    }
+}
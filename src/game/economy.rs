@@ -0,0 +1,168 @@
+use crate::game::colony::{Colony, StructureKind};
+use crate::game::hazard::{accumulate_risk_with, TechModifiers};
+use crate::game::player::PlayerId;
+use crate::game::system::OrbitalBody;
+use serde::{Deserialize, Serialize};
+
+pub const STARTING_CREDITS: f64 = 25.0;
+pub const COLONY_COST: f64 = 10.0;
+pub const OUTPOST_INCOME: f64 = 1.0;
+pub const MAX_MINES_PER_COLONY: usize = 3;
+
+pub fn structure_cost(kind: StructureKind) -> f64 {
+    match kind {
+        StructureKind::Outpost => 5.0,
+        StructureKind::Mine => 8.0,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stockpile {
+    pub credits: f64,
+}
+
+impl Default for Stockpile {
+    fn default() -> Self {
+        Self {
+            credits: STARTING_CREDITS,
+        }
+    }
+}
+
+impl Stockpile {
+    pub fn can_afford(&self, cost: f64) -> bool {
+        self.credits >= cost
+    }
+
+    pub fn spend(&mut self, cost: f64) -> bool {
+        if !self.can_afford(cost) {
+            return false;
+        }
+        self.credits -= cost;
+        true
+    }
+
+    pub fn deposit(&mut self, amount: f64) {
+        self.credits += amount;
+    }
+}
+
+/// What one colony produces in a turn: a flat amount from its outpost, plus
+/// the body's yield for each mine after hazard yield penalties, as eased by
+/// the owner's drilling technology.
+pub fn colony_income(colony: &Colony, body: &OrbitalBody, modifiers: &TechModifiers) -> f64 {
+    let multiplier = accumulate_risk_with(&body.hazards, modifiers).multiplier;
+    colony
+        .structures
+        .iter()
+        .map(|s| match s {
+            StructureKind::Outpost => OUTPOST_INCOME,
+            StructureKind::Mine => body.yield_rate as f64 * multiplier,
+        })
+        .sum()
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ColonyIncome {
+    pub body: u32,
+    pub amount: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct IncomeReport {
+    pub player: PlayerId,
+    pub colonies: Vec<ColonyIncome>,
+    pub total: f64,
+    pub stockpile: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::hazard::{hazard_profile, Hazard, HazardKind};
+    use crate::game::order::{Order, OrderRejection};
+    use crate::game::system::OrbitalKind;
+    use crate::game::tests::colonising_home_body;
+
+    #[test]
+    fn mines_yield_after_hazard_penalties() {
+        let body = OrbitalBody {
+            id: 4,
+            name: "Mine b".to_string(),
+            nickname: None,
+            distance: 80,
+            yield_rate: 5,
+            hazards: vec![Hazard {
+                kind: HazardKind::Radiation,
+                profile: hazard_profile(HazardKind::Radiation),
+            }],
+            kind: OrbitalKind::Planetoid,
+            moons: Vec::new(),
+        };
+        let mut colony = Colony::new(body.id, 0, 1);
+        assert_eq!(
+            colony_income(&colony, &body, &TechModifiers::default()),
+            0.0
+        );
+
+        colony.structures = vec![StructureKind::Outpost, StructureKind::Mine];
+        let expected = OUTPOST_INCOME + 5.0 * (1.0 - 0.4);
+        assert!(
+            (colony_income(&colony, &body, &TechModifiers::default()) - expected).abs() < 1e-12
+        );
+    }
+
+    #[test]
+    fn colonies_cost_credits_and_pay_income_each_tick() {
+        let (mut game, body) = colonising_home_body(12);
+        game.submit(
+            0,
+            Order::Build {
+                body: body.id,
+                structure: StructureKind::Mine,
+            },
+        )
+        .expect_err("colony does not exist until the order resolves");
+        let report = game.tick();
+        assert_eq!(
+            game.players()[0].stockpile.credits,
+            STARTING_CREDITS - COLONY_COST
+        );
+        assert_eq!(report.income[0].total, 0.0);
+
+        game.submit(
+            0,
+            Order::Build {
+                body: body.id,
+                structure: StructureKind::Mine,
+            },
+        )
+        .unwrap();
+        let report = game.tick();
+        let income = colony_income(
+            game.colony(body.id).unwrap(),
+            &body,
+            &TechModifiers::default(),
+        );
+        assert_eq!(report.income[0].colonies[0].amount, income);
+        assert_eq!(
+            game.players()[0].stockpile.credits,
+            STARTING_CREDITS - COLONY_COST - structure_cost(StructureKind::Mine) + income
+        );
+
+        game.players[0].stockpile.credits = 1.0;
+        assert_eq!(
+            game.submit(
+                0,
+                Order::Build {
+                    body: body.id,
+                    structure: StructureKind::Outpost,
+                },
+            ),
+            Err(OrderRejection::InsufficientCredits {
+                cost: structure_cost(StructureKind::Outpost),
+                available: 1.0,
+            })
+        );
+    }
+}
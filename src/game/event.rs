@@ -0,0 +1,429 @@
+use crate::game::fleet::FleetId;
+use crate::game::hazard::HazardKind;
+use crate::game::knowledge::BodyIntel;
+use crate::game::naming::{maybe, themed_adjective, themed_noun};
+use crate::game::player::{Player, PlayerId};
+use crate::game::replay::Checksum;
+use crate::game::system::Universe;
+use crate::game::victory::VictoryCondition;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// Something worth telling the player about, as data.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GameEvent {
+    ProbeLost {
+        player: PlayerId,
+        body: u32,
+    },
+    HazardFlare {
+        player: PlayerId,
+        fleet: FleetId,
+        system: u32,
+        hazard: HazardKind,
+        damage: f64,
+    },
+    PirateRaid {
+        player: PlayerId,
+        fleet: FleetId,
+        system: u32,
+        damage: f64,
+        ships_lost: u32,
+    },
+    ColonyFounded {
+        player: PlayerId,
+        body: u32,
+    },
+    SystemDiscovered {
+        player: PlayerId,
+        system: u32,
+    },
+    GameWon {
+        player: PlayerId,
+        condition: VictoryCondition,
+    },
+}
+
+impl GameEvent {
+    /// The player the event happened to.
+    pub fn player(&self) -> PlayerId {
+        match self {
+            GameEvent::ProbeLost { player, .. }
+            | GameEvent::HazardFlare { player, .. }
+            | GameEvent::PirateRaid { player, .. }
+            | GameEvent::ColonyFounded { player, .. }
+            | GameEvent::SystemDiscovered { player, .. }
+            | GameEvent::GameWon { player, .. } => *player,
+        }
+    }
+}
+
+/// A published event with the prose the side panel shows for it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub turn: u32,
+    #[serde(flatten)]
+    pub event: GameEvent,
+    pub text: String,
+}
+
+/// Collects events while a tick runs and publishes them, narrated, once it
+/// has finished. Published events are kept for the whole game.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EventBus {
+    #[serde(skip)]
+    queued: Vec<GameEvent>,
+    /// Events published before a rewind. Replayed turns reuse their prose,
+    /// so games narrated by an older build still match their checksums.
+    #[serde(skip)]
+    recorded: Vec<Event>,
+    log: Vec<Event>,
+}
+
+impl EventBus {
+    pub fn emit(&mut self, event: GameEvent) {
+        self.queued.push(event);
+    }
+
+    pub fn publish(&mut self, turn: u32, universe: &Universe, players: &[Player]) {
+        for event in std::mem::take(&mut self.queued) {
+            let text = match self.recorded.get(self.log.len()) {
+                Some(past) if past.turn == turn && past.event == event => past.text.clone(),
+                _ => narrate(universe, players, turn, &event),
+            };
+            self.log.push(Event { turn, event, text });
+        }
+    }
+
+    /// Replays publish `events`' prose again instead of narrating afresh,
+    /// until `forget_recorded`.
+    pub(crate) fn record(&mut self, events: &[Event]) {
+        self.recorded = events.to_vec();
+    }
+
+    pub(crate) fn forget_recorded(&mut self) {
+        self.recorded.clear();
+    }
+
+    pub fn all(&self) -> &[Event] {
+        &self.log
+    }
+
+    /// Events published on `turn`, in the order they happened.
+    pub fn turn(&self, turn: u32) -> &[Event] {
+        let start = self.log.partition_point(|e| e.turn < turn);
+        let end = self.log.partition_point(|e| e.turn <= turn);
+        &self.log[start..end]
+    }
+}
+
+impl Event {
+    /// Whether `viewer` hears of this event: their own, plus a win, which
+    /// everyone does. `None` hears everything.
+    pub fn seen_by(&self, viewer: Option<PlayerId>) -> bool {
+        viewer.is_none_or(|v| v == self.event.player())
+            || matches!(self.event, GameEvent::GameWon { .. })
+    }
+}
+
+#[derive(Serialize)]
+struct TurnLogView<'a> {
+    turn: u32,
+    events: Vec<&'a Event>,
+}
+
+pub fn turn_log_json(bus: &EventBus, turn: u32, viewer: Option<PlayerId>) -> String {
+    let view = TurnLogView {
+        turn,
+        events: bus
+            .turn(turn)
+            .iter()
+            .filter(|e| e.seen_by(viewer))
+            .collect(),
+    };
+    serde_json::to_string(&view).unwrap_or_else(|_| "{}".to_string())
+}
+
+const OPENERS: &[&str] = &[
+    "Word reaches the capital:",
+    "From the long-range logs:",
+    "Dispatch:",
+    "Late signal:",
+];
+
+fn hazard_phrase(kind: HazardKind) -> &'static str {
+    match kind {
+        HazardKind::Radiation => "radiation storm",
+        HazardKind::Pirates => "raid",
+        HazardKind::Debris => "debris swarm",
+    }
+}
+
+/// Prose for `event`. The wording is drawn from a generator seeded by the
+/// event itself, so it never touches the game's RNG streams and reads the
+/// same on every replay.
+pub fn narrate(universe: &Universe, players: &[Player], turn: u32, event: &GameEvent) -> String {
+    let seed = serde_json::to_string(event).unwrap_or_default();
+    let mut rng = ChaCha8Rng::seed_from_u64(Checksum::of(seed.as_bytes()).0 ^ turn as u64);
+
+    let faction = |id: PlayerId| {
+        players
+            .iter()
+            .find(|p| p.id == id)
+            .map_or_else(|| format!("Faction {}", id), |p| p.name.clone())
+    };
+    let system_name = |id: u32| {
+        universe
+            .system(id)
+            .and_then(|s| s.stars.first())
+            .map_or_else(|| format!("system {}", id), |s| s.name.clone())
+    };
+    let body_name = |id: u32| {
+        universe
+            .locate_body(id)
+            .map_or_else(|| format!("body {}", id), |(_, b)| b.name.clone())
+    };
+    let body_hazards = |id: u32| -> Vec<HazardKind> {
+        universe.locate_body(id).map_or_else(Vec::new, |(_, b)| {
+            b.hazards.iter().map(|h| h.kind).collect()
+        })
+    };
+    // Only what `player` has surveyed, so the prose gives nothing away.
+    let known_hazards = |player: PlayerId, id: u32| -> Vec<HazardKind> {
+        let surveyed = players
+            .iter()
+            .any(|p| p.id == player && p.knowledge.body_intel(id) == Some(BodyIntel::Surveyed));
+        if surveyed {
+            body_hazards(id)
+        } else {
+            Vec::new()
+        }
+    };
+
+    let line = match event {
+        GameEvent::ProbeLost { player, body } => {
+            let adj = themed_adjective(&mut rng, &known_hazards(*player, *body));
+            let templates = [
+                format!(
+                    "The {} lost contact with its probe over {}.",
+                    faction(*player),
+                    body_name(*body)
+                ),
+                format!(
+                    "The {} dark above {} swallowed a {} probe.",
+                    adj.to_lowercase(),
+                    body_name(*body),
+                    faction(*player)
+                ),
+                format!(
+                    "A {} probe went quiet somewhere near {}.",
+                    faction(*player),
+                    body_name(*body)
+                ),
+            ];
+            pick_line(&mut rng, templates)
+        }
+        GameEvent::HazardFlare {
+            fleet,
+            system,
+            hazard,
+            damage,
+            ..
+        } => {
+            let noun = themed_noun(&mut rng, &[*hazard]);
+            let templates = [
+                format!(
+                    "A {} battered fleet {} at {}, costing {:.2} hull.",
+                    hazard_phrase(*hazard),
+                    fleet,
+                    system_name(*system),
+                    damage
+                ),
+                format!(
+                    "{} flared as fleet {} arrived; the crews call it the {}.",
+                    system_name(*system),
+                    fleet,
+                    noun
+                ),
+            ];
+            pick_line(&mut rng, templates)
+        }
+        GameEvent::PirateRaid {
+            player,
+            fleet,
+            system,
+            ships_lost,
+            ..
+        } => {
+            let adj = themed_adjective(&mut rng, &[HazardKind::Pirates]);
+            let raid = pick_line(
+                &mut rng,
+                [
+                    format!(
+                        "{} raiders fell on fleet {} of the {} at {}.",
+                        adj,
+                        fleet,
+                        faction(*player),
+                        system_name(*system)
+                    ),
+                    format!(
+                        "Pirates out of {} struck fleet {} of the {}.",
+                        system_name(*system),
+                        fleet,
+                        faction(*player)
+                    ),
+                ],
+            );
+            match ships_lost {
+                0 => raid,
+                1 => format!("{} One ship did not come home.", raid),
+                n => format!("{} {} ships did not come home.", raid, n),
+            }
+        }
+        GameEvent::ColonyFounded { player, body } => {
+            let hazards = body_hazards(*body);
+            let adj = themed_adjective(&mut rng, &hazards);
+            let noun = themed_noun(&mut rng, &hazards);
+            let templates = [
+                format!(
+                    "The {} raised its flag on {}.",
+                    faction(*player),
+                    body_name(*body)
+                ),
+                format!(
+                    "Settlers of the {} made landfall on {}, naming their first camp {} {}.",
+                    faction(*player),
+                    body_name(*body),
+                    adj,
+                    noun
+                ),
+            ];
+            pick_line(&mut rng, templates)
+        }
+        GameEvent::SystemDiscovered { player, system } => {
+            let templates = [
+                format!(
+                    "Charts of the {} now show {}.",
+                    faction(*player),
+                    system_name(*system)
+                ),
+                format!(
+                    "Scouts of the {} sighted {} for the first time.",
+                    faction(*player),
+                    system_name(*system)
+                ),
+            ];
+            pick_line(&mut rng, templates)
+        }
+        GameEvent::GameWon { player, condition } => {
+            let feat = match condition {
+                VictoryCondition::Survey { .. } => "mapping the known stars",
+                VictoryCondition::Control { .. } => "holding the systems it settled",
+                VictoryCondition::Wealth { .. } => "filling its treasury",
+                VictoryCondition::Survive { .. } => "outlasting its rivals",
+            };
+            let templates = [
+                format!("The {} has won the game by {}.", faction(*player), feat),
+                format!(
+                    "History will remember the {} for {}.",
+                    faction(*player),
+                    feat
+                ),
+            ];
+            pick_line(&mut rng, templates)
+        }
+    };
+
+    match maybe(&mut rng, OPENERS, 0.25) {
+        Some(opener) => format!("{} {}", opener, line),
+        None => line,
+    }
+}
+
+fn pick_line<const N: usize>(rng: &mut ChaCha8Rng, lines: [String; N]) -> String {
+    let idx = rng.gen_range(0..N);
+    lines.into_iter().nth(idx).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::nickname::ENGLISH;
+    use crate::game::tests::colonising_home_body;
+    use crate::game::Game;
+
+    #[test]
+    fn narration_is_deterministic_and_names_the_parties() {
+        let game = Game::with_factions(8, 2);
+        let body = game.universe().systems[0].orbitals[0].clone();
+        let event = GameEvent::ProbeLost {
+            player: 1,
+            body: body.id,
+        };
+        let text = narrate(game.universe(), game.players(), 3, &event);
+        assert_eq!(text, narrate(game.universe(), game.players(), 3, &event));
+        assert!(text.contains(&body.name), "{text}");
+        assert!(text.contains(&game.players()[1].name), "{text}");
+    }
+
+    #[test]
+    fn probe_losses_only_hint_at_hazards_the_faction_has_surveyed() {
+        let mut game = Game::with_factions(8, 2);
+        let body = game
+            .universe()
+            .bodies()
+            .find(|b| {
+                !b.hazards.is_empty() && game.players()[0].knowledge.body_intel(b.id).is_none()
+            })
+            .expect("some hazardous body is unsurveyed")
+            .clone();
+        let themed: Vec<String> = body
+            .hazards
+            .iter()
+            .flat_map(|h| ENGLISH.theme(h.kind).adjectives)
+            .filter(|a| !ENGLISH.adjectives.contains(a))
+            .map(|a| a.forms[0].to_lowercase())
+            .collect();
+        let event = GameEvent::ProbeLost {
+            player: 0,
+            body: body.id,
+        };
+        let hints = |game: &Game| {
+            (0..200)
+                .map(|turn| narrate(game.universe(), game.players(), turn, &event))
+                .filter(|text| themed.iter().any(|w| text.contains(w.as_str())))
+                .count()
+        };
+
+        assert_eq!(hints(&game), 0);
+        game.players[0].knowledge.record_survey(body.id);
+        assert!(hints(&game) > 0);
+    }
+
+    #[test]
+    fn ticks_publish_a_turn_log() {
+        let (mut game, body) = colonising_home_body(12);
+        game.tick();
+        game.tick();
+
+        let log: serde_json::Value =
+            serde_json::from_str(&turn_log_json(game.events(), 1, None)).unwrap();
+        assert_eq!(log["turn"], 1);
+        let events = log["events"].as_array().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["kind"], "colony_founded");
+        assert_eq!(events[0]["body"], body.id);
+        assert!(events[0]["text"].as_str().unwrap().contains(&body.name));
+        assert!(game.events().turn(2).is_empty());
+
+        let heard = |viewer| {
+            let log: serde_json::Value =
+                serde_json::from_str(&turn_log_json(game.events(), 1, viewer)).unwrap();
+            log["events"].as_array().unwrap().len()
+        };
+        assert_eq!(heard(Some(0)), 1);
+        assert_eq!(heard(Some(1)), 0);
+    }
+}
@@ -0,0 +1,412 @@
+use crate::game::name_filter::UsedNames;
+use crate::game::naming::StyleSequences;
+use crate::game::player::PlayerId;
+use crate::game::replay::Checksum;
+use crate::game::system::Universe;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+const FACTION_TITLES: &[&str] = &[
+    "Compact",
+    "Union",
+    "Dominion",
+    "Collective",
+    "League",
+    "Concord",
+];
+
+/// Each faction draws from its own ChaCha stream of the master seed, so one
+/// faction's dice never shift another's.
+pub fn faction_rng(seed: u64, faction: PlayerId) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(faction as u64 + 1);
+    rng
+}
+
+/// The title follows from the root, so naming a faction spends none of its
+/// dice.
+pub fn faction_name(roots: &mut StyleSequences, faction: PlayerId, used: &mut UsedNames) -> String {
+    let root = roots.next_faction_name(faction, used);
+    let title =
+        FACTION_TITLES[(Checksum::of(root.as_bytes()).0 % FACTION_TITLES.len() as u64) as usize];
+    format!("{} {}", root, title)
+}
+
+/// Hop counts between every pair of systems; `u32::MAX` where unreachable.
+pub fn link_distances(universe: &Universe) -> Vec<Vec<u32>> {
+    let n = universe.systems.len();
+    let mut all = Vec::with_capacity(n);
+    for start in 0..n {
+        let mut dist = vec![u32::MAX; n];
+        let mut queue = VecDeque::from([start]);
+        dist[start] = 0;
+        while let Some(at) = queue.pop_front() {
+            for &next in &universe.systems[at].links {
+                let next = next as usize;
+                if dist[next] == u32::MAX {
+                    dist[next] = dist[at] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        all.push(dist);
+    }
+    all
+}
+
+/// How dangerous a start is: hazards in the system itself, plus half weight
+/// for each neighbour a new faction is likely to expand into.
+pub fn hazard_exposure(universe: &Universe, system: u32) -> f64 {
+    let local = |id: u32| -> f64 {
+        universe
+            .system_hazards(id)
+            .iter()
+            .map(|h| h.profile.probe_fail + h.profile.hull_damage)
+            .sum()
+    };
+    let neighbours: f64 = universe
+        .system(system)
+        .map(|sys| sys.links.iter().map(|&l| local(l)).sum())
+        .unwrap_or(0.0);
+    local(system) + 0.5 * neighbours
+}
+
+fn spread(values: impl Iterator<Item = f64>) -> f64 {
+    let (lo, hi) = values.fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    if lo > hi {
+        0.0
+    } else {
+        hi - lo
+    }
+}
+
+struct HomeScore {
+    nearest_rival: u32,
+    rival_spread: f64,
+    exposure_spread: f64,
+    total_exposure: f64,
+}
+
+impl HomeScore {
+    fn fairer_than(&self, other: &HomeScore) -> bool {
+        self.nearest_rival
+            .cmp(&other.nearest_rival)
+            .then(other.rival_spread.total_cmp(&self.rival_spread))
+            .then(other.exposure_spread.total_cmp(&self.exposure_spread))
+            .then(other.total_exposure.total_cmp(&self.total_exposure))
+            .is_gt()
+    }
+}
+
+/// Picks `count` home systems that sit as far apart on the link graph as
+/// possible, with each faction's nearest rival at a similar distance and
+/// similar hazard exposure around every start.
+pub fn choose_home_systems(universe: &Universe, count: usize) -> Vec<u32> {
+    let n = universe.systems.len();
+    let count = count.min(n);
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let dist = link_distances(universe);
+    let exposure: Vec<f64> = (0..n as u32)
+        .map(|s| hazard_exposure(universe, s))
+        .collect();
+
+    // Greedy farthest-point selection from every possible first home, then
+    // keep the fairest of the candidate sets.
+    let mut best: Option<(Vec<u32>, HomeScore)> = None;
+    for start in 0..n {
+        let mut homes = vec![start];
+        while homes.len() < count {
+            let next = (0..n)
+                .filter(|c| !homes.contains(c))
+                .max_by(|&a, &b| {
+                    let da = homes.iter().map(|&h| dist[h][a]).min().unwrap_or(0);
+                    let db = homes.iter().map(|&h| dist[h][b]).min().unwrap_or(0);
+                    let ea = (exposure[a] - exposure[start]).abs();
+                    let eb = (exposure[b] - exposure[start]).abs();
+                    da.cmp(&db).then(eb.total_cmp(&ea)).then(b.cmp(&a))
+                })
+                .expect("fewer homes than systems");
+            homes.push(next);
+        }
+
+        let nearest: Vec<u32> = homes
+            .iter()
+            .map(|&h| {
+                homes
+                    .iter()
+                    .filter(|&&o| o != h)
+                    .map(|&o| dist[h][o])
+                    .min()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let score = HomeScore {
+            nearest_rival: nearest.iter().copied().min().unwrap_or(0),
+            rival_spread: spread(nearest.iter().map(|&d| d as f64)),
+            exposure_spread: spread(homes.iter().map(|&h| exposure[h])),
+            total_exposure: homes.iter().map(|&h| exposure[h]).sum(),
+        };
+        let better = best
+            .as_ref()
+            .is_none_or(|(_, current)| score.fairer_than(current));
+        if better {
+            best = Some((homes.iter().map(|&h| h as u32).collect(), score));
+        }
+    }
+
+    best.map(|(homes, _)| homes).unwrap_or_default()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stance {
+    War,
+    Neutral,
+    Alliance,
+}
+
+/// Symmetric stances between factions; pairs never set are neutral. War
+/// and neutrality take effect as soon as either side declares them, but an
+/// alliance needs both: a one-sided offer waits in `proposals` until the
+/// other faction proposes back.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(into = "DiplomacyRecord", from = "DiplomacyRecord")]
+pub struct Diplomacy {
+    stances: BTreeMap<(PlayerId, PlayerId), Stance>,
+    proposals: BTreeSet<(PlayerId, PlayerId)>,
+}
+
+impl Diplomacy {
+    fn key(a: PlayerId, b: PlayerId) -> (PlayerId, PlayerId) {
+        (a.min(b), a.max(b))
+    }
+
+    pub fn stance(&self, a: PlayerId, b: PlayerId) -> Stance {
+        self.stances
+            .get(&Self::key(a, b))
+            .copied()
+            .unwrap_or(Stance::Neutral)
+    }
+
+    /// `a` declares `stance` toward `b` and gets back the stance now in
+    /// force between them, which stays put when `a` only offered an
+    /// alliance.
+    pub fn set_stance(&mut self, a: PlayerId, b: PlayerId, stance: Stance) -> Stance {
+        if stance == Stance::Alliance && !self.proposals.remove(&(b, a)) {
+            self.proposals.insert((a, b));
+            return self.stance(a, b);
+        }
+        self.proposals.remove(&(a, b));
+        self.proposals.remove(&(b, a));
+        self.stances.insert(Self::key(a, b), stance);
+        stance
+    }
+
+    /// Whether `a` has offered `b` an alliance that `b` has not answered.
+    pub fn proposed(&self, a: PlayerId, b: PlayerId) -> bool {
+        self.proposals.contains(&(a, b))
+    }
+
+    pub fn allied(&self, a: PlayerId, b: PlayerId) -> bool {
+        a == b || self.stance(a, b) == Stance::Alliance
+    }
+}
+
+/// Saved form of `Diplomacy`: the bare list of stances while no alliance
+/// offer is pending, so games without one save exactly as before.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum DiplomacyRecord {
+    Stances(Vec<(PlayerId, PlayerId, Stance)>),
+    WithProposals {
+        stances: Vec<(PlayerId, PlayerId, Stance)>,
+        proposals: Vec<(PlayerId, PlayerId)>,
+    },
+}
+
+impl From<Diplomacy> for DiplomacyRecord {
+    fn from(diplomacy: Diplomacy) -> Self {
+        let stances = diplomacy
+            .stances
+            .into_iter()
+            .map(|((a, b), stance)| (a, b, stance))
+            .collect();
+        if diplomacy.proposals.is_empty() {
+            DiplomacyRecord::Stances(stances)
+        } else {
+            DiplomacyRecord::WithProposals {
+                stances,
+                proposals: diplomacy.proposals.into_iter().collect(),
+            }
+        }
+    }
+}
+
+impl From<DiplomacyRecord> for Diplomacy {
+    fn from(record: DiplomacyRecord) -> Self {
+        let (stances, proposals) = match record {
+            DiplomacyRecord::Stances(stances) => (stances, Vec::new()),
+            DiplomacyRecord::WithProposals { stances, proposals } => (stances, proposals),
+        };
+        Diplomacy {
+            stances: stances
+                .into_iter()
+                .map(|(a, b, stance)| (Self::key(a, b), stance))
+                .collect(),
+            proposals: proposals.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::naming::{NameStyle, NamingConfig};
+    use crate::game::order::Order;
+    use crate::game::replay::replay;
+    use crate::game::system::{UniverseConfig, UniverseGenerator};
+    use crate::game::victory::VictoryConfig;
+    use crate::game::Game;
+    use rand::Rng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn two_homes_sit_at_opposite_ends_of_the_graph() {
+        let config = UniverseConfig {
+            systems: 10,
+            ..Default::default()
+        };
+        let universe = UniverseGenerator::with_config(4, config).generate();
+        let dist = link_distances(&universe);
+        let diameter = dist.iter().flatten().copied().max().unwrap();
+
+        let homes = choose_home_systems(&universe, 2);
+        assert_eq!(homes.len(), 2);
+        assert_eq!(dist[homes[0] as usize][homes[1] as usize], diameter);
+
+        let homes = choose_home_systems(&universe, 4);
+        let unique: HashSet<_> = homes.iter().collect();
+        assert_eq!(unique.len(), 4);
+    }
+
+    #[test]
+    fn factions_get_independent_streams_and_shared_diplomacy() {
+        let mut a = faction_rng(9, 0);
+        let mut b = faction_rng(9, 1);
+        let mut a_again = faction_rng(9, 0);
+        let first: u64 = a.gen();
+        assert_eq!(first, a_again.gen::<u64>());
+        assert_ne!(first, b.gen::<u64>());
+
+        let mut game = Game::with_factions(9, 3);
+        assert_eq!(game.players().len(), 3);
+        let homes: HashSet<_> = game.players().iter().map(|p| p.home_system).collect();
+        assert_eq!(homes.len(), 3);
+        for player in game.players() {
+            assert!(player.knowledge.knows_system(player.home_system));
+        }
+
+        game.submit(
+            2,
+            Order::SetStance {
+                toward: 0,
+                stance: Stance::Alliance,
+            },
+        )
+        .unwrap();
+        game.tick();
+        assert_eq!(game.diplomacy().stance(0, 2), Stance::Neutral);
+        assert!(game.diplomacy().proposed(2, 0));
+
+        game.submit(
+            0,
+            Order::SetStance {
+                toward: 2,
+                stance: Stance::Alliance,
+            },
+        )
+        .unwrap();
+        game.tick();
+        assert_eq!(game.diplomacy().stance(0, 2), Stance::Alliance);
+        assert!(!game.diplomacy().proposed(2, 0));
+        assert_eq!(game.diplomacy().stance(1, 2), Stance::Neutral);
+
+        game.submit(
+            1,
+            Order::SetStance {
+                toward: 2,
+                stance: Stance::War,
+            },
+        )
+        .unwrap();
+        game.tick();
+        assert_eq!(game.diplomacy().stance(1, 2), Stance::War);
+    }
+
+    #[test]
+    fn one_sided_alliance_offers_reveal_nothing() {
+        let mut game = Game::with_factions(9, 3);
+        let alone: serde_json::Value = serde_json::from_str(&game.universe_json(Some(2))).unwrap();
+        game.submit(
+            0,
+            Order::SetStance {
+                toward: 2,
+                stance: Stance::Alliance,
+            },
+        )
+        .unwrap();
+        game.tick();
+        assert!(!game.diplomacy().allied(0, 2));
+
+        let view: serde_json::Value = serde_json::from_str(&game.universe_json(Some(2))).unwrap();
+        let owners: HashSet<u64> = view["fleets"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["owner"].as_u64().unwrap())
+            .collect();
+        assert!(!owners.contains(&0));
+        assert_eq!(view["fleets"], alone["fleets"]);
+
+        let saved = serde_json::to_string(game.diplomacy()).unwrap();
+        let loaded: Diplomacy = serde_json::from_str(&saved).unwrap();
+        assert!(loaded.proposed(0, 2));
+    }
+
+    #[test]
+    fn factions_are_named_in_their_configured_style() {
+        let naming = NamingConfig {
+            factions: vec![NameStyle::Catalogue],
+            ..Default::default()
+        };
+        let mut game = Game::with_naming(9, 2, VictoryConfig::default(), naming);
+        let names: Vec<String> = game.players().iter().map(|p| p.name.clone()).collect();
+        assert!(
+            ["HD ", "HIP ", "GJ ", "KIC ", "TYC "]
+                .iter()
+                .any(|prefix| names[0].starts_with(prefix)),
+            "{}",
+            names[0]
+        );
+        assert!(
+            !names[1].chars().any(|c| c.is_ascii_digit()),
+            "{}",
+            names[1]
+        );
+
+        game.tick();
+        let log = game.order_log().unwrap().clone();
+        let replayed = replay(
+            &serde_json::from_str(&serde_json::to_string(&log).unwrap()).unwrap(),
+            1,
+        )
+        .expect("replays on the same names");
+        let replayed: Vec<String> = replayed.players().iter().map(|p| p.name.clone()).collect();
+        assert_eq!(replayed, names);
+    }
+}
@@ -0,0 +1,307 @@
+use crate::game::hazard::Hazard;
+use crate::game::player::PlayerId;
+use crate::game::probe::roll_hull_damage;
+use rand_chacha::ChaCha8Rng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub type FleetId = u32;
+
+const STARTING_PROBES: u32 = 3;
+/// Distance along any link; a fleet covers `engine` of it per turn.
+pub const LINK_LENGTH: u32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HullClass {
+    Scout,
+    Frigate,
+    Freighter,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShipDesign {
+    pub hull: HullClass,
+    pub armour: f64,
+    pub engine: u32,
+    pub sensors: u32,
+    pub cargo: u32,
+}
+
+pub fn ship_design(hull: HullClass) -> ShipDesign {
+    match hull {
+        HullClass::Scout => ShipDesign {
+            hull,
+            armour: 1.0,
+            engine: 3,
+            sensors: 2,
+            cargo: 0,
+        },
+        HullClass::Frigate => ShipDesign {
+            hull,
+            armour: 2.5,
+            engine: 2,
+            sensors: 1,
+            cargo: 1,
+        },
+        HullClass::Freighter => ShipDesign {
+            hull,
+            armour: 1.5,
+            engine: 1,
+            sensors: 1,
+            cargo: 4,
+        },
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Ship {
+    pub design: ShipDesign,
+    pub integrity: f64,
+}
+
+impl Ship {
+    pub fn new(hull: HullClass) -> Self {
+        let design = ship_design(hull);
+        Self {
+            design,
+            integrity: design.armour,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transit {
+    pub to: u32,
+    pub progress: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Fleet {
+    pub id: FleetId,
+    pub owner: PlayerId,
+    /// The system the fleet is docked at, or departed from while in transit.
+    pub system: u32,
+    pub transit: Option<Transit>,
+    pub ships: Vec<Ship>,
+    pub probes: u32,
+    /// Systems still to visit, nearest first.
+    pub route: Vec<u32>,
+}
+
+impl Fleet {
+    pub fn new(id: FleetId, owner: PlayerId, system: u32, ships: Vec<Ship>) -> Self {
+        Self {
+            id,
+            owner,
+            system,
+            transit: None,
+            ships,
+            probes: 0,
+            route: Vec::new(),
+        }
+    }
+
+    pub fn starting(id: FleetId, owner: PlayerId, system: u32) -> Self {
+        Self {
+            probes: STARTING_PROBES,
+            ..Self::new(id, owner, system, vec![Ship::new(HullClass::Scout)])
+        }
+    }
+
+    /// Fleets move at the pace of their slowest ship.
+    pub fn speed(&self) -> u32 {
+        self.ships
+            .iter()
+            .map(|s| s.design.engine)
+            .min()
+            .unwrap_or(0)
+    }
+
+    pub fn sensors(&self) -> u32 {
+        self.ships
+            .iter()
+            .map(|s| s.design.sensors)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn cargo(&self) -> u32 {
+        self.ships.iter().map(|s| s.design.cargo).sum()
+    }
+
+    pub fn is_docked(&self) -> bool {
+        self.transit.is_none()
+    }
+
+    /// Where the fleet will be once its current hop, if any, completes.
+    pub fn next_stop(&self) -> u32 {
+        self.transit.map_or(self.system, |t| t.to)
+    }
+
+    /// Turns until the fleet reaches the end of its route; `None` when it is
+    /// idle or cannot move.
+    pub fn eta(&self) -> Option<u32> {
+        let speed = self.speed();
+        if speed == 0 || (self.transit.is_none() && self.route.is_empty()) {
+            return None;
+        }
+        let per_hop = LINK_LENGTH.div_ceil(speed);
+        let current = self
+            .transit
+            .map_or(0, |t| (LINK_LENGTH - t.progress).div_ceil(speed));
+        Some(current + per_hop * self.route.len() as u32)
+    }
+
+    /// Rolls hazard damage for every ship, scrapping those worn down to
+    /// nothing. Returns the total damage taken and the hulls that were lost.
+    pub fn take_hazard_damage(
+        &mut self,
+        rng: &mut ChaCha8Rng,
+        hazards: &[Hazard],
+    ) -> (f64, Vec<HullClass>) {
+        let mut total = 0.0;
+        for ship in &mut self.ships {
+            let damage = roll_hull_damage(rng, hazards);
+            ship.integrity -= damage;
+            total += damage;
+        }
+        let lost = self
+            .ships
+            .iter()
+            .filter(|s| s.integrity <= 0.0)
+            .map(|s| s.design.hull)
+            .collect();
+        self.ships.retain(|s| s.integrity > 0.0);
+        (total, lost)
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ShipView {
+    hull: HullClass,
+    integrity: f64,
+    armour: f64,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct FleetView {
+    id: FleetId,
+    owner: PlayerId,
+    system: u32,
+    destination: Option<u32>,
+    progress: f64,
+    eta: Option<u32>,
+    route: Vec<u32>,
+    probes: u32,
+    ships: Vec<ShipView>,
+}
+
+impl From<&Fleet> for FleetView {
+    fn from(fleet: &Fleet) -> Self {
+        Self {
+            id: fleet.id,
+            owner: fleet.owner,
+            system: fleet.system,
+            destination: fleet.transit.map(|t| t.to),
+            progress: fleet
+                .transit
+                .map_or(0.0, |t| t.progress as f64 / LINK_LENGTH as f64),
+            eta: fleet.eta(),
+            route: fleet.route.clone(),
+            probes: fleet.probes,
+            ships: fleet
+                .ships
+                .iter()
+                .map(|s| ShipView {
+                    hull: s.design.hull,
+                    integrity: s.integrity,
+                    armour: s.design.armour,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::order::Order;
+    use crate::game::report::ReportEntry;
+    use crate::game::Game;
+    use serde_json::Value;
+
+    #[test]
+    fn slowest_ship_sets_speed_and_eta() {
+        let mut fleet = Fleet::new(
+            1,
+            0,
+            0,
+            vec![Ship::new(HullClass::Scout), Ship::new(HullClass::Frigate)],
+        );
+        assert_eq!(fleet.speed(), 2);
+        assert_eq!(fleet.sensors(), 2);
+        assert_eq!(fleet.eta(), None);
+
+        fleet.route = vec![1, 2];
+        assert_eq!(fleet.eta(), Some(4));
+        fleet.transit = Some(Transit { to: 1, progress: 2 });
+        fleet.route = vec![2];
+        assert_eq!(fleet.eta(), Some(3));
+    }
+
+    #[test]
+    fn freighters_take_several_turns_per_link() {
+        let mut game = Game::new(31);
+        let home = game.players()[0].home_system;
+        let route: Vec<u32> = game.universe().systems[home as usize].links[..1].to_vec();
+        game.fleets.push(Fleet::new(
+            1,
+            0,
+            home,
+            vec![Ship::new(HullClass::Freighter)],
+        ));
+        game.submit(
+            0,
+            Order::SetRoute {
+                fleet: 1,
+                route: route.clone(),
+            },
+        )
+        .unwrap();
+
+        for turn in 1..LINK_LENGTH {
+            let report = game.tick();
+            assert!(!report
+                .entries
+                .iter()
+                .any(|e| matches!(e, ReportEntry::FleetMoved { fleet: 1, .. })));
+            let view: Value = serde_json::from_str(&game.universe_json(Some(0))).unwrap();
+            let fleet = view["fleets"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|f| f["id"] == 1)
+                .unwrap();
+            assert_eq!(fleet["destination"], route[0]);
+            assert_eq!(fleet["eta"], LINK_LENGTH - turn);
+        }
+
+        let report = game.tick();
+        assert!(report.entries.iter().any(|e| matches!(
+            e,
+            ReportEntry::FleetMoved { fleet: 1, to, .. } if *to == route[0]
+        )));
+        let fleet = game.fleet(1).unwrap();
+        assert!(fleet.is_docked());
+        assert_eq!(fleet.system, route[0]);
+
+        let exposed = game
+            .universe()
+            .system_hazards(route[0])
+            .iter()
+            .any(|h| h.profile.hull_damage > 0.0);
+        let damaged = fleet.ships[0].integrity < fleet.ships[0].design.armour;
+        assert_eq!(exposed, damaged);
+    }
+}
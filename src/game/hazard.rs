@@ -1,18 +1,20 @@
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum HazardKind {
     Radiation,
     Pirates,
     Debris,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct HazardProfile {
     pub probe_fail: f64,
     pub hull_damage: f64,
     pub yield_penalty: f64,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Hazard {
     pub kind: HazardKind,
     pub profile: HazardProfile,
@@ -70,6 +72,12 @@ impl RiskChannels {
     }
 }
 
+impl Default for RiskChannels {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub fn apply_hazard(h: &Hazard, acc: &mut RiskChannels) {
     match h.kind {
         HazardKind::Radiation => {
@@ -89,3 +97,54 @@ pub fn apply_hazard(h: &Hazard, acc: &mut RiskChannels) {
         }
     }
 }
+
+pub fn accumulate_risk(hazards: &[Hazard]) -> RiskChannels {
+    let mut acc = RiskChannels::new();
+    for h in hazards {
+        apply_hazard(h, &mut acc);
+    }
+    acc
+}
+
+/// Scales hazard profiles for a player's technology. Every roll and every
+/// analytic estimate for that player goes through the adjusted hazards, so
+/// `RiskChannels::failure_prob` keeps matching what they actually face.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TechModifiers {
+    pub hull_damage: f64,
+    pub radiation_probe_fail: f64,
+    pub yield_penalty: f64,
+}
+
+impl Default for TechModifiers {
+    fn default() -> Self {
+        Self {
+            hull_damage: 1.0,
+            radiation_probe_fail: 1.0,
+            yield_penalty: 1.0,
+        }
+    }
+}
+
+impl TechModifiers {
+    pub fn adjust(&self, hazard: &Hazard) -> Hazard {
+        let mut profile = hazard.profile;
+        profile.hull_damage *= self.hull_damage;
+        profile.yield_penalty *= self.yield_penalty;
+        if hazard.kind == HazardKind::Radiation {
+            profile.probe_fail *= self.radiation_probe_fail;
+        }
+        Hazard {
+            kind: hazard.kind,
+            profile,
+        }
+    }
+
+    pub fn apply(&self, hazards: &[Hazard]) -> Vec<Hazard> {
+        hazards.iter().map(|h| self.adjust(h)).collect()
+    }
+}
+
+pub fn accumulate_risk_with(hazards: &[Hazard], modifiers: &TechModifiers) -> RiskChannels {
+    accumulate_risk(&modifiers.apply(hazards))
+}
@@ -0,0 +1,242 @@
+use crate::game::hazard::TechModifiers;
+use crate::game::probe::ProbeOutcome;
+use crate::game::system::{probe_failure_with, OrbitalBody};
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+const ESTIMATE_SPREAD: f64 = 0.1;
+const MIN_ESTIMATE_SPREAD: f64 = 0.025;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BodyIntel {
+    /// Telemetry from lost probes: the true failure chance lies within
+    /// `probe_failure ± spread`, hazards are still unknown.
+    Estimated { probe_failure: f64, spread: f64 },
+    /// A probe came back; the viewer sees the body's truth fields.
+    Surveyed,
+}
+
+/// What one viewer has learned about the universe. Systems must be revealed
+/// before their bodies are listed, and bodies must be surveyed before their
+/// hazards are.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Knowledge {
+    systems: BTreeSet<u32>,
+    bodies: BTreeMap<u32, BodyIntel>,
+}
+
+impl Knowledge {
+    pub const fn new() -> Self {
+        Self {
+            systems: BTreeSet::new(),
+            bodies: BTreeMap::new(),
+        }
+    }
+
+    pub fn reveal_system(&mut self, system_id: u32) {
+        self.systems.insert(system_id);
+    }
+
+    pub fn knows_system(&self, system_id: u32) -> bool {
+        self.systems.contains(&system_id)
+    }
+
+    pub fn known_systems(&self) -> usize {
+        self.systems.len()
+    }
+
+    pub fn surveyed_bodies(&self) -> usize {
+        self.bodies
+            .values()
+            .filter(|intel| matches!(intel, BodyIntel::Surveyed))
+            .count()
+    }
+
+    pub fn body_intel(&self, body_id: u32) -> Option<BodyIntel> {
+        self.bodies.get(&body_id).copied()
+    }
+
+    pub fn is_surveyed(&self, body_id: u32) -> bool {
+        matches!(self.body_intel(body_id), Some(BodyIntel::Surveyed))
+    }
+
+    pub fn record_survey(&mut self, body_id: u32) {
+        self.bodies.insert(body_id, BodyIntel::Surveyed);
+    }
+
+    pub fn record_outcome(
+        &mut self,
+        rng: &mut ChaCha8Rng,
+        body: &OrbitalBody,
+        outcome: &ProbeOutcome,
+        modifiers: &TechModifiers,
+    ) {
+        match outcome {
+            ProbeOutcome::Surveyed { .. } => self.record_survey(body.id),
+            ProbeOutcome::Lost { .. } => {
+                let spread = match self.body_intel(body.id) {
+                    Some(BodyIntel::Surveyed) => return,
+                    Some(BodyIntel::Estimated { spread, .. }) => {
+                        (spread / 2.0).max(MIN_ESTIMATE_SPREAD)
+                    }
+                    None => ESTIMATE_SPREAD,
+                };
+                let truth = probe_failure_with(&body.hazards, modifiers);
+                let noise = rng.gen_range(-spread..=spread);
+                self.bodies.insert(
+                    body.id,
+                    BodyIntel::Estimated {
+                        probe_failure: (truth + noise).clamp(0.0, 0.95),
+                        spread,
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::hazard::HazardKind;
+    use crate::game::naming::describe_body;
+    use crate::game::nickname::ENGLISH;
+    use crate::game::probe::launch_probe;
+    use crate::game::system::{universe_view_json, UniverseGenerator};
+    use rand::SeedableRng;
+    use serde_json::Value;
+
+    #[test]
+    fn viewer_only_sees_revealed_systems_and_surveyed_hazards() {
+        let universe = UniverseGenerator::new(77).generate();
+        let system = &universe.systems[0];
+        let body = &system.orbitals[0];
+
+        let mut knowledge = Knowledge::new();
+        let view: Value =
+            serde_json::from_str(&universe_view_json(&universe, Some(&knowledge), Vec::new()))
+                .unwrap();
+        assert!(view["systems"].as_array().unwrap().is_empty());
+
+        knowledge.reveal_system(system.id);
+        let view: Value =
+            serde_json::from_str(&universe_view_json(&universe, Some(&knowledge), Vec::new()))
+                .unwrap();
+        let orbital = &view["systems"][0]["orbitals"][0];
+        assert_eq!(view["systems"].as_array().unwrap().len(), 1);
+        assert_eq!(orbital["name"], body.name.as_str());
+        assert_eq!(orbital["intel"], "unknown");
+        assert!(orbital["hazards"].is_null());
+        assert!(orbital["probe_failure"].is_null());
+        assert_eq!(
+            orbital["description"],
+            describe_body(body, &[], None).as_str()
+        );
+        assert!(view["systems"][0]["links"].as_array().unwrap().is_empty());
+
+        let neighbour = system.links[0];
+        knowledge.reveal_system(neighbour);
+        let view: Value =
+            serde_json::from_str(&universe_view_json(&universe, Some(&knowledge), Vec::new()))
+                .unwrap();
+        assert_eq!(view["systems"][0]["links"], serde_json::json!([neighbour]));
+
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let outcome = loop {
+            let outcome = launch_probe(&mut rng, body);
+            if !outcome.is_lost() {
+                break outcome;
+            }
+        };
+        knowledge.record_outcome(&mut rng, body, &outcome, &TechModifiers::default());
+        let view: Value =
+            serde_json::from_str(&universe_view_json(&universe, Some(&knowledge), Vec::new()))
+                .unwrap();
+        let orbital = &view["systems"][0]["orbitals"][0];
+        assert_eq!(orbital["intel"], "surveyed");
+        assert_eq!(
+            orbital["hazards"].as_array().unwrap().len(),
+            body.hazards.len()
+        );
+        let kinds: Vec<_> = body.hazards.iter().map(|h| h.kind).collect();
+        assert_eq!(
+            orbital["description"],
+            describe_body(body, &kinds, body.nickname.as_deref()).as_str()
+        );
+    }
+
+    #[test]
+    fn unsurveyed_bodies_keep_their_nickname_and_theme_hidden() {
+        let universe = UniverseGenerator::new(77).generate();
+        let (system, body) = universe
+            .systems
+            .iter()
+            .flat_map(|s| s.orbitals.iter().map(move |b| (s, b)))
+            .find(|(_, b)| b.nickname.is_some() && !b.hazards.is_empty())
+            .expect("some hazardous body has a nickname");
+        let mut knowledge = Knowledge::new();
+        knowledge.reveal_system(system.id);
+        let view: Value =
+            serde_json::from_str(&universe_view_json(&universe, Some(&knowledge), Vec::new()))
+                .unwrap();
+        let orbital = view["systems"][0]["orbitals"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|o| o["id"] == body.id)
+            .expect("the body is in its system's view");
+        assert!(orbital["nickname"].is_null());
+
+        let text = orbital.to_string();
+        assert!(!text.contains(body.nickname.as_deref().unwrap()), "{text}");
+        let words: Vec<&str> = text.split(|c: char| !c.is_alphabetic()).collect();
+        for kind in [
+            HazardKind::Radiation,
+            HazardKind::Pirates,
+            HazardKind::Debris,
+        ] {
+            let theme = ENGLISH.theme(kind);
+            let themed = theme
+                .adjectives
+                .iter()
+                .flat_map(|a| a.forms)
+                .chain(theme.nouns.iter().map(|n| n.word));
+            for word in themed {
+                assert!(!words.contains(&word), "{word} in {text}");
+            }
+        }
+    }
+
+    #[test]
+    fn lost_probes_narrow_the_estimate_around_the_truth() {
+        let universe = UniverseGenerator::new(78).generate();
+        let body = &universe.systems[0].orbitals[0];
+        let truth = probe_failure_with(&body.hazards, &TechModifiers::default());
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut knowledge = Knowledge::new();
+
+        let mut last_spread = f64::MAX;
+        for _ in 0..4 {
+            knowledge.record_outcome(
+                &mut rng,
+                body,
+                &ProbeOutcome::Lost { body_id: body.id },
+                &TechModifiers::default(),
+            );
+            match knowledge.body_intel(body.id) {
+                Some(BodyIntel::Estimated {
+                    probe_failure,
+                    spread,
+                }) => {
+                    assert!((probe_failure - truth).abs() <= spread + 1e-12);
+                    assert!(spread <= last_spread);
+                    last_spread = spread;
+                }
+                other => panic!("expected an estimate, got {:?}", other),
+            }
+        }
+        assert_eq!(last_spread, MIN_ESTIMATE_SPREAD);
+    }
+}
@@ -1,21 +1,423 @@
+pub mod ai;
 pub mod body;
+pub mod canonical;
+pub mod colony;
+pub mod economy;
+pub mod event;
+pub mod faction;
+pub mod fleet;
 pub mod hazard;
+pub mod knowledge;
+pub mod name_filter;
 pub mod naming;
+pub mod nickname;
+pub mod order;
+pub mod player;
+pub mod probe;
+pub mod replay;
+pub mod report;
+pub mod save;
+pub mod scenario;
+pub mod status;
 pub mod system;
+pub mod tech;
+pub mod victory;
 
+use colony::{Colony, StructureKind};
+use economy::{colony_income, structure_cost, ColonyIncome, IncomeReport, COLONY_COST};
+use event::{EventBus, GameEvent};
+use faction::{choose_home_systems, faction_name, faction_rng, Diplomacy, Stance};
+use fleet::{Fleet, FleetId, FleetView, HullClass, Transit, LINK_LENGTH};
+use hazard::{Hazard, HazardKind, TechModifiers};
+use knowledge::Knowledge;
+use name_filter::UsedNames;
+use naming::{NamingConfig, StyleSequences};
+use nickname::Locale;
+use order::{Order, OrderRejection, PendingOrder};
+use player::{Player, PlayerId};
+use probe::{ProbeOutcome, TrialSummary};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
+use replay::{Checksum, LoggedTurn, OrderLog, ReplayError, Snapshot, SNAPSHOT_INTERVAL};
+use report::{Phase, ReportEntry, TurnReport};
+use serde::{Deserialize, Serialize};
+use system::{
+    system_view, universe_view, OrbitalBody, Universe, UniverseConfig, UniverseGenerator,
+};
+use tech::tech_info;
+use victory::{Victory, VictoryConfig};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Game {
     turn: u32,
+    #[serde(with = "save::rng_state")]
     rng: ChaCha8Rng,
+    universe: Universe,
+    players: Vec<Player>,
+    fleets: Vec<Fleet>,
+    colonies: Vec<Colony>,
+    diplomacy: Diplomacy,
+    pending: Vec<PendingOrder>,
+    events: EventBus,
+    victory: VictoryConfig,
+    outcome: Option<Victory>,
+    /// Absent only for games saved before logs were kept.
+    log: Option<OrderLog>,
+    #[serde(skip)]
+    snapshots: Vec<Snapshot>,
+}
+
+/// The parts of `Game` its checksum covers: everything but the order log,
+/// which a replay is checked against rather than one that rebuilds it.
+#[derive(Serialize)]
+struct ChecksumState<'a> {
+    turn: u32,
+    #[serde(with = "save::rng_state")]
+    rng: &'a ChaCha8Rng,
+    universe: &'a Universe,
+    players: &'a [Player],
+    fleets: &'a [Fleet],
+    colonies: &'a [Colony],
+    diplomacy: &'a Diplomacy,
+    pending: &'a [PendingOrder],
+    events: &'a EventBus,
+    victory: &'a VictoryConfig,
+    outcome: Option<&'a Victory>,
+}
+
+/// The map a game on `seed` is generated with, with room for every faction.
+fn generate_universe(seed: u64, factions: usize, naming: &NamingConfig) -> Universe {
+    let defaults = UniverseConfig::default();
+    let config = UniverseConfig {
+        systems: defaults.systems.max(factions),
+        naming: naming.clone(),
+        ..defaults
+    };
+    UniverseGenerator::with_config(seed, config).generate()
 }
 
 impl Game {
     pub fn new(seed: u64) -> Self {
-        Self {
+        Self::with_factions(seed, 1)
+    }
+
+    /// Starts a game for `factions` players, each with a scout at a home
+    /// system picked by `choose_home_systems`.
+    pub fn with_factions(seed: u64, factions: usize) -> Self {
+        Self::with_victory(seed, factions, VictoryConfig::default())
+    }
+
+    /// Like `with_factions`, played to the given victory conditions.
+    pub fn with_victory(seed: u64, factions: usize, victory: VictoryConfig) -> Self {
+        Self::with_naming(seed, factions, victory, NamingConfig::default())
+    }
+
+    /// Like `with_victory`, with stars and factions named by `naming`.
+    pub fn with_naming(
+        seed: u64,
+        factions: usize,
+        victory: VictoryConfig,
+        naming: NamingConfig,
+    ) -> Self {
+        let universe = generate_universe(seed, factions, &naming);
+        if matches!(naming.nicknames, Locale::Custom(_)) {
+            return Self::with_universe(seed, factions, victory, naming, universe);
+        }
+        let mut log = OrderLog::new(seed, factions, victory);
+        log.naming = naming;
+        Self::start(log, universe)
+    }
+
+    /// Starts a game on a hand-authored universe, such as one from
+    /// `scenario::load_universe`. The universe is kept in the order log so
+    /// replays and rewinds rebuild the same map rather than a generated one,
+    /// which is also how games with a custom nickname lexicon are replayed.
+    pub fn with_universe(
+        seed: u64,
+        factions: usize,
+        victory: VictoryConfig,
+        naming: NamingConfig,
+        universe: Universe,
+    ) -> Self {
+        let mut log = OrderLog::new(seed, factions, victory);
+        log.universe = Some(universe.clone());
+        log.naming = match naming.nicknames {
+            Locale::Custom(_) => NamingConfig {
+                nicknames: Locale::default(),
+                ..naming
+            },
+            _ => naming,
+        };
+        Self::start(log, universe)
+    }
+
+    /// The game as it stood before the first turn of `log`.
+    fn from_log(log: &OrderLog) -> Self {
+        let setup = OrderLog {
+            universe: log.universe.clone(),
+            naming: log.naming.clone(),
+            starts: log.starts.clone(),
+            ..OrderLog::new(log.seed, log.factions, log.victory.clone())
+        };
+        let universe = match &log.universe {
+            Some(universe) => universe.clone(),
+            None => generate_universe(log.seed, log.factions, &log.naming),
+        };
+        Self::start(setup, universe)
+    }
+
+    fn start(log: OrderLog, universe: Universe) -> Self {
+        let seed = log.seed;
+        let naming = log.naming.clone();
+        let homes = choose_home_systems(&universe, log.factions);
+
+        let mut roots = StyleSequences::new(&naming, Checksum::of(b"factions").0 ^ seed);
+        let mut used_names = UsedNames::new();
+        let mut players = Vec::with_capacity(homes.len());
+        let mut fleets = Vec::with_capacity(homes.len());
+        for (id, home) in homes.into_iter().enumerate() {
+            let id = id as PlayerId;
+            let player = match log.starts.get(id as usize) {
+                Some(start) => Player::new(id, start.name.clone(), home, start.rng.clone()),
+                None => {
+                    let name = faction_name(&mut roots, id, &mut used_names);
+                    Player::new(id, name, home, faction_rng(seed, id))
+                }
+            };
+            players.push(player);
+            fleets.push(Fleet::starting(id, id, home));
+        }
+
+        let mut game = Self {
             turn: 0,
             rng: ChaCha8Rng::seed_from_u64(seed),
+            universe,
+            players,
+            fleets,
+            colonies: Vec::new(),
+            diplomacy: Diplomacy::default(),
+            pending: Vec::new(),
+            events: EventBus::default(),
+            victory: log.victory.clone(),
+            outcome: None,
+            log: Some(log),
+            snapshots: Vec::new(),
+        };
+        game.take_snapshot();
+        game
+    }
+
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn universe(&self) -> &Universe {
+        &self.universe
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    pub fn player(&self, id: PlayerId) -> Option<&Player> {
+        self.players.iter().find(|p| p.id == id)
+    }
+
+    pub fn fleets(&self) -> &[Fleet] {
+        &self.fleets
+    }
+
+    pub fn fleet(&self, id: FleetId) -> Option<&Fleet> {
+        self.fleets.iter().find(|f| f.id == id)
+    }
+
+    pub fn colonies(&self) -> &[Colony] {
+        &self.colonies
+    }
+
+    pub fn colony(&self, body: u32) -> Option<&Colony> {
+        self.colonies.iter().find(|c| c.body == body)
+    }
+
+    pub fn diplomacy(&self) -> &Diplomacy {
+        &self.diplomacy
+    }
+
+    pub fn pending_orders(&self) -> &[PendingOrder] {
+        &self.pending
+    }
+
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    pub fn victory_config(&self) -> &VictoryConfig {
+        &self.victory
+    }
+
+    /// Set once a player meets a victory condition; no orders are taken after.
+    pub fn outcome(&self) -> Option<&Victory> {
+        self.outcome.as_ref()
+    }
+
+    /// Every order resolved so far, turn by turn; see `replay::replay`.
+    pub fn order_log(&self) -> Option<&OrderLog> {
+        self.log.as_ref()
+    }
+
+    /// A fingerprint of the whole game state apart from the log itself.
+    pub fn checksum(&self) -> Checksum {
+        // Spelled out so a new field can't slip past the checksum unnoticed.
+        let Game {
+            turn,
+            rng,
+            universe,
+            players,
+            fleets,
+            colonies,
+            diplomacy,
+            pending,
+            events,
+            victory,
+            outcome,
+            log: _,
+            snapshots: _,
+        } = self;
+        let state = ChecksumState {
+            turn: *turn,
+            rng,
+            universe,
+            players,
+            fleets,
+            colonies,
+            diplomacy,
+            pending,
+            events,
+            victory,
+            outcome: outcome.as_ref(),
+        };
+        Checksum::of(&canonical::to_vec(&state).unwrap_or_default())
+    }
+
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    fn take_snapshot(&mut self) {
+        let state = self.clone_state();
+        self.snapshots.push(Snapshot {
+            turn: self.turn,
+            checksum: state.checksum(),
+            state: Box::new(state),
+        });
+    }
+
+    /// Clones everything but the log and snapshots, which only grow.
+    fn clone_state(&self) -> Game {
+        Game {
+            turn: self.turn,
+            rng: self.rng.clone(),
+            universe: self.universe.clone(),
+            players: self.players.clone(),
+            fleets: self.fleets.clone(),
+            colonies: self.colonies.clone(),
+            diplomacy: self.diplomacy.clone(),
+            pending: self.pending.clone(),
+            events: self.events.clone(),
+            victory: self.victory.clone(),
+            outcome: self.outcome.clone(),
+            log: None,
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Steps back to the state after `turn` and forgets every later turn, so
+    /// play branches from there. Starts from the nearest snapshot at or
+    /// before `turn` and replays the logged orders since.
+    pub fn rewind(&mut self, turn: u32) -> Result<(), ReplayError> {
+        let Some(log) = self.log.as_ref() else {
+            return Err(ReplayError::NoLog);
+        };
+        if turn > self.turn {
+            return Err(ReplayError::TurnOutOfRange {
+                turn,
+                last: self.turn,
+            });
+        }
+
+        let mut game = match self.snapshots.iter().rev().find(|s| s.turn <= turn) {
+            Some(snapshot) => snapshot.state.clone_state(),
+            None => Game::from_log(log),
+        };
+        let start = game.turn;
+        game.events.record(self.events.all());
+        replay::replay_turns(
+            &mut game,
+            log.turns
+                .iter()
+                .filter(|t| t.turn > start && t.turn <= turn),
+        )?;
+        game.events.forget_recorded();
+
+        let mut log = log.clone();
+        log.turns.retain(|t| t.turn <= turn);
+        let mut snapshots = std::mem::take(&mut self.snapshots);
+        snapshots.retain(|s| s.turn <= turn);
+        for snapshot in game.snapshots.drain(..) {
+            if !snapshots.iter().any(|s| s.turn == snapshot.turn) {
+                snapshots.push(snapshot);
+            }
+        }
+        snapshots.sort_by_key(|s| s.turn);
+        game.log = Some(log);
+        game.snapshots = snapshots;
+        *self = game;
+        Ok(())
+    }
+
+    /// The universe as `viewer` knows it, with the fleets they can see: their
+    /// own and their allies', plus any docked at or heading into a system
+    /// they know.
+    pub fn universe_json(&self, viewer: Option<PlayerId>) -> String {
+        serde_json::to_string(&self.universe_view(viewer)).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// The universe as `viewer` sees it, fleets included, ready to
+    /// serialise; `universe_json` is this as JSON.
+    pub fn universe_view(&self, viewer: Option<PlayerId>) -> impl Serialize {
+        let (knowledge, modifiers) = self.seat_view(viewer);
+        let fleets = self
+            .fleets
+            .iter()
+            .filter(|f| match (viewer, knowledge) {
+                (Some(id), Some(k)) => {
+                    self.diplomacy.allied(id, f.owner)
+                        || k.knows_system(f.system)
+                        || k.knows_system(f.next_stop())
+                }
+                _ => true,
+            })
+            .map(FleetView::from)
+            .collect();
+        universe_view(&self.universe, knowledge, &modifiers, fleets)
+    }
+
+    /// One system from `universe_view`, or `None` if `viewer` has not seen
+    /// it.
+    pub fn system_view(&self, id: u32, viewer: Option<PlayerId>) -> Option<impl Serialize> {
+        let (knowledge, modifiers) = self.seat_view(viewer);
+        system_view(&self.universe, id, knowledge, &modifiers)
+    }
+
+    /// Only `None` is omniscient; a player id with no seat knows nothing.
+    fn seat_view(&self, viewer: Option<PlayerId>) -> (Option<&Knowledge>, TechModifiers) {
+        static UNSEATED: Knowledge = Knowledge::new();
+        let Some(id) = viewer else {
+            return (None, TechModifiers::default());
+        };
+        match self.player(id) {
+            Some(seat) => (Some(&seat.knowledge), seat.research.modifiers()),
+            None => (Some(&UNSEATED), TechModifiers::default()),
         }
     }
 
@@ -27,29 +429,361 @@ impl Game {
         self.rng.gen()
     }
 
-    pub fn tick(&mut self) -> u32 {
+    pub fn launch_probe(&mut self, body: &OrbitalBody) -> ProbeOutcome {
+        probe::launch_probe(&mut self.rng, body)
+    }
+
+    pub fn probe_trials(&mut self, body: &OrbitalBody, trials: u32) -> TrialSummary {
+        probe::run_trials(&mut self.rng, body, trials)
+    }
+
+    /// Validates and queues an order; it resolves on the next `tick`.
+    pub fn submit(&mut self, player: PlayerId, order: Order) -> Result<(), OrderRejection> {
+        order::validate(self, player, &order)?;
+        self.pending.push(PendingOrder { player, order });
+        Ok(())
+    }
+
+    /// Advances one turn, resolving queued orders phase by phase. Within a
+    /// phase orders run by player id, then in the order they were submitted.
+    pub fn tick(&mut self) -> TurnReport {
         self.turn += 1;
-        self.turn
+        let mut orders = std::mem::take(&mut self.pending);
+        orders.sort_by_key(|pending| pending.player);
+
+        let mut report = TurnReport::new(self.turn);
+        for phase in Phase::ALL {
+            for pending in orders.iter().filter(|p| p.order.phase() == phase) {
+                if let Err(rejection) = self.resolve(pending, &mut report) {
+                    report.push(ReportEntry::OrderFailed {
+                        player: pending.player,
+                        phase,
+                        rejection,
+                    });
+                }
+            }
+            if phase == Phase::Movement {
+                self.advance_fleets(&mut report);
+            }
+        }
+        self.collect_income(&mut report);
+        if self.outcome.is_none() {
+            self.outcome = victory::evaluate(self, &self.victory);
+            if let Some(won) = &self.outcome {
+                self.events.emit(GameEvent::GameWon {
+                    player: won.player,
+                    condition: won.condition.clone(),
+                });
+            }
+        }
+        self.events
+            .publish(self.turn, &self.universe, &self.players);
+
+        if self.log.is_some() {
+            let checksum = self.checksum();
+            if let Some(log) = self.log.as_mut() {
+                log.turns.push(LoggedTurn {
+                    turn: self.turn,
+                    orders,
+                    checksum,
+                });
+            }
+        }
+        if self.turn.is_multiple_of(SNAPSHOT_INTERVAL) {
+            self.take_snapshot();
+        }
+        report
+    }
+
+    fn collect_income(&mut self, report: &mut TurnReport) {
+        for player in self.players.iter_mut() {
+            let colonies: Vec<ColonyIncome> = self
+                .colonies
+                .iter()
+                .filter(|c| c.owner == player.id)
+                .filter_map(|c| {
+                    let (_, body) = self.universe.locate_body(c.body)?;
+                    Some(ColonyIncome {
+                        body: c.body,
+                        amount: colony_income(c, body, &player.research.modifiers()),
+                    })
+                })
+                .collect();
+            let total = colonies.iter().map(|c| c.amount).sum();
+            player.stockpile.deposit(total);
+            report.income.push(IncomeReport {
+                player: player.id,
+                colonies,
+                total,
+                stockpile: player.stockpile.credits,
+            });
+        }
+    }
+
+    fn resolve(
+        &mut self,
+        pending: &PendingOrder,
+        report: &mut TurnReport,
+    ) -> Result<(), OrderRejection> {
+        let player = pending.player;
+        order::validate(self, player, &pending.order)?;
+        match &pending.order {
+            Order::SetStance { toward, stance } => {
+                let now = self.diplomacy.set_stance(player, *toward, *stance);
+                report.push(if *stance == Stance::Alliance && now != Stance::Alliance {
+                    ReportEntry::AllianceProposed {
+                        player,
+                        toward: *toward,
+                    }
+                } else {
+                    ReportEntry::StanceChanged {
+                        player,
+                        toward: *toward,
+                        stance: now,
+                    }
+                });
+            }
+            Order::MoveFleet { fleet, to } => self.set_route(*fleet, vec![*to]),
+            Order::SetRoute { fleet, route } => self.set_route(*fleet, route.clone()),
+            Order::LaunchProbe { fleet, body } => self.resolve_probe(player, *fleet, *body, report),
+            Order::Colonise { body, .. } => {
+                self.spend(player, COLONY_COST);
+                self.colonies.push(Colony::new(*body, player, self.turn));
+                self.events.emit(GameEvent::ColonyFounded {
+                    player,
+                    body: *body,
+                });
+                report.push(ReportEntry::ColonyFounded {
+                    player,
+                    body: *body,
+                });
+            }
+            Order::Build { body, structure } => self.build(player, *body, *structure, report),
+            Order::Research { tech } => {
+                self.spend(player, tech_info(*tech).cost);
+                if let Some(p) = self.players.iter_mut().find(|p| p.id == player) {
+                    p.research.unlock(*tech);
+                }
+                report.push(ReportEntry::TechResearched {
+                    player,
+                    tech: *tech,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn set_route(&mut self, fleet: FleetId, route: Vec<u32>) {
+        if let Some(f) = self.fleets.iter_mut().find(|f| f.id == fleet) {
+            f.route = route;
+        }
+    }
+
+    fn advance_fleets(&mut self, report: &mut TurnReport) {
+        for fleet in self.fleets.iter_mut() {
+            if fleet.transit.is_none() && !fleet.route.is_empty() {
+                let to = fleet.route.remove(0);
+                fleet.transit = Some(Transit { to, progress: 0 });
+            }
+            let Some(mut transit) = fleet.transit else {
+                continue;
+            };
+            transit.progress += fleet.speed();
+            if transit.progress < LINK_LENGTH {
+                fleet.transit = Some(transit);
+                continue;
+            }
+
+            let from = fleet.system;
+            let to = transit.to;
+            fleet.system = to;
+            fleet.transit = None;
+            report.push(ReportEntry::FleetMoved {
+                fleet: fleet.id,
+                from,
+                to,
+            });
+
+            let player = fleet.owner;
+            let Some(owner) = self.players.iter_mut().find(|p| p.id == player) else {
+                continue;
+            };
+            // Long-range sensors also pick up the systems linked to this one.
+            let mut sighted = vec![to];
+            if fleet.sensors() >= 2 {
+                if let Some(sys) = self.universe.system(to) {
+                    sighted.extend(sys.links.iter().copied());
+                }
+            }
+            for system in sighted {
+                if !owner.knowledge.knows_system(system) {
+                    owner.knowledge.reveal_system(system);
+                    report.push(ReportEntry::SystemRevealed { player, system });
+                    self.events
+                        .emit(GameEvent::SystemDiscovered { player, system });
+                }
+            }
+
+            let hazards = self.universe.system_hazards(to);
+            if hazards.is_empty() {
+                continue;
+            }
+            let felt = owner.research.modifiers().apply(&hazards);
+            let (damage, lost) = fleet.take_hazard_damage(&mut owner.rng, &felt);
+            report.push(ReportEntry::FleetDamaged {
+                fleet: fleet.id,
+                system: to,
+                damage,
+            });
+            if damage > 0.0 {
+                self.events
+                    .emit(hazard_event(player, fleet.id, to, &hazards, damage, &lost));
+            }
+            for hull in lost {
+                report.push(ReportEntry::ShipLost {
+                    fleet: fleet.id,
+                    hull,
+                });
+            }
+            if fleet.ships.is_empty() {
+                report.push(ReportEntry::FleetDestroyed { fleet: fleet.id });
+            }
+        }
+        self.fleets.retain(|f| !f.ships.is_empty());
+    }
+
+    fn resolve_probe(
+        &mut self,
+        player: PlayerId,
+        fleet: FleetId,
+        body_id: u32,
+        report: &mut TurnReport,
+    ) {
+        let Some((_, body)) = self.universe.locate_body(body_id) else {
+            return;
+        };
+        let Some(owner) = self.players.iter_mut().find(|p| p.id == player) else {
+            return;
+        };
+        if let Some(f) = self.fleets.iter_mut().find(|f| f.id == fleet) {
+            f.probes -= 1;
+        }
+        let modifiers = owner.research.modifiers();
+        let outcome = probe::launch_probe_with(&mut owner.rng, body, &modifiers);
+        owner
+            .knowledge
+            .record_outcome(&mut owner.rng, body, &outcome, &modifiers);
+        if outcome.is_lost() {
+            self.events.emit(GameEvent::ProbeLost {
+                player,
+                body: body_id,
+            });
+        }
+        report.push(match outcome {
+            ProbeOutcome::Lost { .. } => ReportEntry::ProbeLost {
+                player,
+                body: body_id,
+            },
+            ProbeOutcome::Surveyed { hull_damage, .. } => ReportEntry::BodySurveyed {
+                player,
+                body: body_id,
+                hull_damage,
+            },
+        });
+    }
+
+    fn spend(&mut self, player: PlayerId, cost: f64) {
+        if let Some(p) = self.players.iter_mut().find(|p| p.id == player) {
+            p.stockpile.spend(cost);
+        }
+    }
+
+    fn build(
+        &mut self,
+        player: PlayerId,
+        body: u32,
+        structure: StructureKind,
+        report: &mut TurnReport,
+    ) {
+        self.spend(player, structure_cost(structure));
+        if let Some(colony) = self.colonies.iter_mut().find(|c| c.body == body) {
+            colony.structures.push(structure);
+            report.push(ReportEntry::StructureBuilt {
+                player,
+                body,
+                structure,
+            });
+        }
     }
 }
 
-pub fn greeting_for(game: &mut Game) -> String {
-    let number = game.next_u32();
-    format!("Hello, more-space2! My random number is: {}", number)
+/// Pirates in the system make any damage a raid; otherwise it is put down
+/// to whichever hazard there is hardest on hulls.
+fn hazard_event(
+    player: PlayerId,
+    fleet: FleetId,
+    system: u32,
+    hazards: &[Hazard],
+    damage: f64,
+    lost: &[HullClass],
+) -> GameEvent {
+    if hazards.iter().any(|h| h.kind == HazardKind::Pirates) {
+        return GameEvent::PirateRaid {
+            player,
+            fleet,
+            system,
+            damage,
+            ships_lost: lost.len() as u32,
+        };
+    }
+    let hazard = hazards
+        .iter()
+        .max_by(|a, b| a.profile.hull_damage.total_cmp(&b.profile.hull_damage))
+        .map_or(HazardKind::Debris, |h| h.kind);
+    GameEvent::HazardFlare {
+        player,
+        fleet,
+        system,
+        hazard,
+        damage,
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::hazard::{
-        apply_hazard, hazard_profile, Hazard, HazardKind, RiskChannels,
+    use super::colony::StructureKind;
+    use super::hazard::{apply_hazard, hazard_profile, Hazard, HazardKind, RiskChannels};
+    use super::name_filter::{phonetic_key, within_distance, UsedNames};
+    use super::naming::{
+        describe_body, describe_system, MarkovModel, NameSequence, NameStyle, NamingConfig, Shuffle,
     };
-    use super::naming::generate_star_name;
-    use super::system::{UniverseConfig, UniverseGenerator};
+    use super::order::{submit_json, Order, OrderRejection};
+    use super::report::{Phase, ReportEntry};
+    use super::system::{OrbitalBody, StarSystem, UniverseConfig, UniverseGenerator};
     use super::Game;
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
     use std::collections::HashSet;
 
+    /// A one-faction game on `seed` whose faction has surveyed the first
+    /// body of its home system and ordered a colony there; the colony is
+    /// founded by the next tick.
+    pub(crate) fn colonising_home_body(seed: u64) -> (Game, OrbitalBody) {
+        let mut game = Game::new(seed);
+        let home = game.players()[0].home_system;
+        let body = game.universe().systems[home as usize].orbitals[0].clone();
+        game.players[0].knowledge.record_survey(body.id);
+        game.submit(
+            0,
+            Order::Colonise {
+                fleet: 0,
+                body: body.id,
+            },
+        )
+        .unwrap();
+        (game, body)
+    }
+
     #[test]
     fn deterministic_with_same_seed() {
         let mut g1 = Game::new(42);
@@ -62,6 +796,146 @@ mod tests {
         assert_eq!(g1.next_u32(), g2.next_u32());
     }
 
+    #[test]
+    fn game_owns_universe_generated_from_its_seed() {
+        let game = Game::new(123);
+        let generated = UniverseGenerator::new(123).generate();
+
+        assert_eq!(game.universe().systems.len(), generated.systems.len());
+        for (a, b) in game.universe().systems.iter().zip(&generated.systems) {
+            assert_eq!(a.stars[0].name, b.stars[0].name);
+            assert_eq!(a.links, b.links);
+        }
+        let home = game.players()[0].home_system;
+        assert!(game.players()[0].knowledge.knows_system(home));
+    }
+
+    #[test]
+    fn tick_resolves_movement_before_probes() {
+        let mut game = Game::new(9);
+        let home = game.players()[0].home_system;
+        let target = game.universe().systems[home as usize].links[0];
+        let body = game.universe().systems[target as usize].orbitals[0].id;
+
+        game.submit(
+            0,
+            Order::MoveFleet {
+                fleet: 0,
+                to: target,
+            },
+        )
+        .unwrap();
+        let report = game.tick();
+        assert_eq!(report.turn, 1);
+        assert_eq!(
+            report.entries[0],
+            ReportEntry::FleetMoved {
+                fleet: 0,
+                from: home,
+                to: target
+            }
+        );
+        assert!(game.players()[0].knowledge.knows_system(target));
+
+        // Probes launched this turn fly after the fleet has moved on.
+        let onward = game.universe().systems[target as usize].links[0];
+        game.submit(0, Order::LaunchProbe { fleet: 0, body })
+            .unwrap();
+        game.submit(
+            0,
+            Order::MoveFleet {
+                fleet: 0,
+                to: onward,
+            },
+        )
+        .unwrap();
+        let report = game.tick();
+        assert!(matches!(
+            report.entries.last(),
+            Some(ReportEntry::OrderFailed {
+                phase: Phase::Probes,
+                rejection: OrderRejection::BodyOutOfReach { .. },
+                ..
+            })
+        ));
+        assert_eq!(game.fleet(0).unwrap().probes, 3);
+        assert!(game.pending_orders().is_empty());
+    }
+
+    #[test]
+    fn orders_are_validated_on_submit_and_on_resolution() {
+        let mut game = Game::new(9);
+        let home = game.players()[0].home_system;
+        let body = game.universe().systems[home as usize].orbitals[0].id;
+        let unlinked = (0..game.universe().systems.len() as u32)
+            .find(|id| *id != home && !game.universe().systems[home as usize].links.contains(id));
+
+        if let Some(to) = unlinked {
+            assert_eq!(
+                game.submit(0, Order::MoveFleet { fleet: 0, to }),
+                Err(OrderRejection::NotLinked { from: home, to })
+            );
+        }
+        assert_eq!(
+            game.submit(1, Order::LaunchProbe { fleet: 0, body }),
+            Err(OrderRejection::UnknownPlayer { player: 1 })
+        );
+        assert_eq!(
+            game.submit(0, Order::Colonise { fleet: 0, body }),
+            Err(OrderRejection::NotSurveyed { body })
+        );
+        assert_eq!(
+            game.submit(
+                0,
+                Order::Build {
+                    body,
+                    structure: StructureKind::Mine
+                }
+            ),
+            Err(OrderRejection::NotYourColony { body })
+        );
+
+        // Each probe order passes on submit, but the fleet only carries three.
+        for _ in 0..4 {
+            game.submit(0, Order::LaunchProbe { fleet: 0, body })
+                .unwrap();
+        }
+        let report = game.tick();
+        assert_eq!(game.fleet(0).unwrap().probes, 0);
+        assert!(matches!(
+            report.entries.last(),
+            Some(ReportEntry::OrderFailed {
+                rejection: OrderRejection::NoProbesLeft { fleet: 0 },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn submit_json_reports_typed_rejections() {
+        let mut game = Game::new(9);
+        let accepted = submit_json(
+            &mut game,
+            r#"{"player":0,"order":{"type":"launch_probe","fleet":0,"body":999}}"#,
+        );
+        assert_eq!(
+            accepted,
+            r#"{"accepted":false,"rejection":{"reason":"unknown_body","body":999}}"#
+        );
+
+        let home = game.players()[0].home_system;
+        let body = game.universe().systems[home as usize].orbitals[0].id;
+        let json =
+            format!(r#"{{"player":0,"order":{{"type":"launch_probe","fleet":0,"body":{body}}}}}"#);
+        assert_eq!(
+            submit_json(&mut game, &json),
+            r#"{"accepted":true,"rejection":null}"#
+        );
+
+        let malformed = submit_json(&mut game, r#"{"player":0,"order":{"type":"warp"}}"#);
+        assert!(malformed.contains(r#""reason":"malformed""#));
+    }
+
     #[test]
     fn risk_channels_yield_expected_failure_probability() {
         let mut acc = RiskChannels::new();
@@ -90,20 +964,18 @@ mod tests {
 
     #[test]
     fn star_name_generation_is_deterministic_and_unique() {
-        let mut rng = ChaCha8Rng::seed_from_u64(7);
-        let mut used = HashSet::new();
-        let names: Vec<String> = (0..5)
-            .map(|_| generate_star_name(&mut rng, &mut used))
-            .collect();
+        let mut used = UsedNames::new();
+        let mut seq = NameSequence::new(NameStyle::Classic, 7);
+        let names: Vec<String> = (0..5).map(|_| seq.next_name(&mut used)).collect();
 
         assert_eq!(
             names,
             vec![
-                "Staisen".to_string(),
-                "Voenm".to_string(),
-                "Driis".to_string(),
-                "Kraearr".to_string(),
-                "Zaerun".to_string()
+                "Thookaior",
+                "Thooshis",
+                "Toodrooun",
+                "Krakaiion",
+                "Dresaieth"
             ]
         );
         assert_eq!(used.len(), names.len());
@@ -111,14 +983,152 @@ mod tests {
 
     #[test]
     fn generates_many_unique_names_without_collision() {
-        let mut rng = ChaCha8Rng::seed_from_u64(99);
-        let mut used = HashSet::new();
+        let mut used = UsedNames::new();
+        let mut seq = NameSequence::new(NameStyle::Classic, 99);
         for _ in 0..64 {
-            generate_star_name(&mut rng, &mut used);
+            seq.next_name(&mut used);
         }
         assert_eq!(used.len(), 64);
     }
 
+    #[test]
+    fn name_sequences_scale_to_large_universes() {
+        let shuffle = Shuffle::new(1000, 3);
+        let hit: HashSet<u64> = (0..1000).map(|i| shuffle.at(i)).collect();
+        assert_eq!(hit.len(), 1000);
+        assert!(hit.iter().all(|&i| i < 1000));
+
+        let mut used = UsedNames::new();
+        let mut seq = NameSequence::new(NameStyle::Classic, 42);
+        let names: Vec<String> = (0..50_000).map(|_| seq.next_name(&mut used)).collect();
+        assert_eq!(used.len(), 50_000);
+        assert!(names
+            .iter()
+            .all(|n| n.chars().next().is_some_and(|c| c.is_ascii_uppercase())));
+
+        // Rebuilding the guard from scratch finds no look-alikes it let through.
+        let mut rebuilt = UsedNames::new();
+        for name in &names {
+            assert_eq!(rebuilt.look_alike(name, 1), None, "{name}");
+            rebuilt.insert(name.clone());
+        }
+
+        let mut again = NameSequence::new(NameStyle::Classic, 42);
+        let mut other = NameSequence::new(NameStyle::Classic, 43);
+        let mut fresh = UsedNames::new();
+        let first: Vec<String> = (0..10).map(|_| again.next_name(&mut fresh)).collect();
+        assert_eq!(first, names[..10]);
+        assert_ne!(other.next_name(&mut UsedNames::new()), names[0]);
+
+        let mut catalogue = NameSequence::new(NameStyle::Catalogue, 7);
+        let mut used = UsedNames::new();
+        for _ in 0..1000 {
+            catalogue.next_name(&mut used);
+        }
+        assert_eq!(used.len(), 1000);
+    }
+
+    #[test]
+    fn generated_names_never_look_alike() {
+        let config = UniverseConfig {
+            systems: 300,
+            ..Default::default()
+        };
+        let universe = UniverseGenerator::with_config(1, config).generate();
+        let names: Vec<&str> = universe
+            .systems
+            .iter()
+            .flat_map(|s| &s.stars)
+            .map(|star| star.name.as_str())
+            .filter(|n| !n.contains(|c: char| c.is_ascii_digit()))
+            .collect();
+        assert!(names.len() > 200);
+        for (i, a) in names.iter().enumerate() {
+            for b in &names[i + 1..] {
+                assert!(!within_distance(a, b, 1), "{a} / {b}");
+                assert_ne!(phonetic_key(a), phonetic_key(b), "{a} / {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn name_styles_follow_their_culture() {
+        let mut used = UsedNames::new();
+        let mut catalogue = NameSequence::new(NameStyle::Catalogue, 5);
+        for _ in 0..20 {
+            let name = catalogue.next_name(&mut used);
+            let (prefix, number) = name.split_once(' ').expect("designation and number");
+            assert!(prefix.chars().all(|c| c.is_ascii_uppercase()), "{name}");
+            assert!(number.parse::<u32>().is_ok(), "{name}");
+        }
+
+        let mut latinate = NameSequence::new(NameStyle::Latinate, 5);
+        let latinate: Vec<String> = (0..20).map(|_| latinate.next_name(&mut used)).collect();
+        assert!(latinate
+            .iter()
+            .all(|n| !n.contains(['k', 'x', 'z']) || n.ends_with("ix")));
+
+        let corpus = [
+            "velora", "veliane", "corvina", "lorena", "morvale", "selvane",
+        ];
+        let model = MarkovModel::train(&corpus, 2);
+        let letters: HashSet<char> = corpus.iter().flat_map(|w| w.chars()).collect();
+        let mut a = ChaCha8Rng::seed_from_u64(8);
+        let mut b = ChaCha8Rng::seed_from_u64(8);
+        for _ in 0..20 {
+            let word = model.generate(&mut a);
+            assert_eq!(word, model.generate(&mut b));
+            assert!(word.len() >= 3, "{word}");
+            assert!(word.chars().all(|c| letters.contains(&c)), "{word}");
+        }
+        assert_eq!(MarkovModel::train::<&str>(&[], 2).generate(&mut a), "");
+
+        // Six examples only spell a few dozen names, most of them one letter
+        // apart, so the sequence soon moves on to designations.
+        let mut markov = NameSequence::new(NameStyle::Markov(model), 5);
+        let names: Vec<String> = (0..20).map(|_| markov.next_name(&mut used)).collect();
+        let spelled = names.iter().take_while(|n| !n.contains(' ')).count();
+        assert!(spelled >= 3, "{names:?}");
+        for name in &names[..spelled] {
+            let name = name.to_ascii_lowercase();
+            assert!((3..=12).contains(&name.len()), "{name}");
+            assert!(name.chars().all(|c| letters.contains(&c)), "{name}");
+        }
+        assert!(
+            names[spelled..].iter().all(|n| n.contains(' ')),
+            "{names:?}"
+        );
+
+        // A model that can spell nothing still names every system.
+        let empty = NameStyle::Markov(MarkovModel::train::<&str>(&[], 2));
+        let mut empty = NameSequence::new(empty, 5);
+        for _ in 0..5 {
+            let name = empty.next_name(&mut used);
+            assert!(name.split_once(' ').is_some(), "{name}");
+        }
+    }
+
+    #[test]
+    fn naming_config_picks_styles_by_region_and_faction() {
+        let config = UniverseConfig {
+            systems: 6,
+            naming: NamingConfig {
+                default: NameStyle::Classic,
+                regions: vec![(3..6, NameStyle::Catalogue)],
+                factions: vec![NameStyle::Catalogue],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let universe = UniverseGenerator::with_config(14, config.clone()).generate();
+        for system in &universe.systems {
+            let catalogued = system.stars[0].name.contains(' ');
+            assert_eq!(catalogued, system.id >= 3, "{}", system.stars[0].name);
+        }
+        assert_eq!(config.naming.faction_style(0), &NameStyle::Catalogue);
+        assert_eq!(config.naming.faction_style(1), &NameStyle::Classic);
+    }
+
     #[test]
     fn system_generation_is_deterministic() {
         let config = UniverseConfig::default();
@@ -160,9 +1170,11 @@ mod tests {
 
     #[test]
     fn naming_is_hierarchical() {
-        let mut config = UniverseConfig::default();
-        config.systems = 1;
-        config.extra_edges = 0;
+        let mut config = UniverseConfig {
+            systems: 1,
+            extra_edges: 0,
+            ..Default::default()
+        };
         config.system.star_count = 1..=1;
         config.system.planetoids = 1..=1;
         config.system.asteroids = 0..=0;
@@ -182,8 +1194,10 @@ mod tests {
 
     #[test]
     fn nicknames_are_unique() {
-        let mut config = UniverseConfig::default();
-        config.systems = 3;
+        let mut config = UniverseConfig {
+            systems: 3,
+            ..Default::default()
+        };
         config.system.star_count = 1..=2;
         config.system.planetoids = 2..=3;
         config.system.asteroids = 1..=1;
@@ -212,4 +1226,123 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn lore_reads_from_hazards_kind_and_nickname() {
+        let universe = UniverseGenerator::new(5).generate();
+        let pirate_den = universe
+            .bodies()
+            .find(|b| b.hazards.len() == 1 && b.hazards[0].kind == HazardKind::Pirates)
+            .expect("some body has only pirates");
+        let lore = describe_body(pirate_den, &[HazardKind::Pirates], None);
+        assert_eq!(
+            lore,
+            describe_body(pirate_den, &[HazardKind::Pirates], None)
+        );
+        assert!(
+            ["Corsairs", "Raiders", "Beacons"]
+                .iter()
+                .any(|w| lore.contains(w)),
+            "{lore}"
+        );
+        let calm = describe_body(pirate_den, &[], None);
+        assert!(
+            !calm.contains("Corsairs") && !calm.contains("Raiders"),
+            "{calm}"
+        );
+
+        let named = universe
+            .bodies()
+            .find(|b| b.nickname.is_some())
+            .expect("some body has a nickname");
+        let nickname = named.nickname.as_deref();
+        assert!(describe_body(named, &[], nickname).contains(nickname.unwrap()));
+        assert!(!describe_body(named, &[], None).contains(nickname.unwrap()));
+
+        let system = &universe.systems[0];
+        let line = describe_system(system);
+        assert_eq!(line, describe_system(system));
+        assert!(line.contains(&system.stars[0].name), "{line}");
+    }
+
+    #[test]
+    fn config_tweaks_leave_unrelated_content_alone() {
+        let config = UniverseConfig {
+            systems: 12,
+            ..Default::default()
+        };
+        let base = UniverseGenerator::with_config(808, config.clone()).generate();
+
+        let mut tweaked = config.clone();
+        tweaked.system.nickname_chance = 0.6;
+        tweaked.system.moons_per_planetoid = 1..=4;
+        tweaked.extra_edges = 9;
+        let other = UniverseGenerator::with_config(808, tweaked).generate();
+
+        let kinds = |body: &OrbitalBody| body.hazards.iter().map(|h| h.kind).collect::<Vec<_>>();
+        let same_nickname = |x: &Option<String>, y: &Option<String>| {
+            if let (Some(x), Some(y)) = (x, y) {
+                assert_eq!(x, y, "a nickname changed for no reason");
+            }
+        };
+        for (a, b) in base.systems.iter().zip(&other.systems) {
+            let names =
+                |sys: &StarSystem| sys.stars.iter().map(|s| s.name.clone()).collect::<Vec<_>>();
+            assert_eq!(names(a), names(b));
+            for (x, y) in a.stars.iter().zip(&b.stars) {
+                same_nickname(&x.nickname, &y.nickname);
+            }
+            assert_eq!(a.orbitals.len(), b.orbitals.len());
+            for (x, y) in a.orbitals.iter().zip(&b.orbitals) {
+                assert_eq!(x.name, y.name);
+                assert_eq!(kinds(x), kinds(y));
+                assert_eq!((x.distance, x.yield_rate), (y.distance, y.yield_rate));
+                same_nickname(&x.nickname, &y.nickname);
+                // Moons that exist in both keep everything they had.
+                for (m, n) in x.moons.iter().zip(&y.moons) {
+                    assert_eq!(m.name, n.name);
+                    assert_eq!(kinds(m), kinds(n));
+                    same_nickname(&m.nickname, &n.nickname);
+                }
+            }
+        }
+        assert!(other.bodies().count() > base.bodies().count());
+        assert!(
+            other.bodies().filter(|b| b.nickname.is_some()).count()
+                > base.bodies().filter(|b| b.nickname.is_some()).count()
+        );
+    }
+
+    #[test]
+    fn system_views_match_the_universe_view() {
+        let game = Game::with_factions(31, 2);
+        let universe: serde_json::Value =
+            serde_json::from_str(&game.universe_json(Some(0))).unwrap();
+        let seen = universe["systems"].as_array().unwrap();
+        assert!(seen.len() < game.universe().systems.len());
+        for sys in &game.universe().systems {
+            let view = game
+                .system_view(sys.id, Some(0))
+                .map(|v| serde_json::to_value(v).unwrap());
+            let listed = seen.iter().find(|s| s["id"] == sys.id).cloned();
+            assert_eq!(view, listed);
+        }
+        assert!(game.system_view(99, None).is_none());
+    }
+
+    #[test]
+    fn unknown_viewers_see_nothing() {
+        let game = Game::with_factions(31, 2);
+        let view: serde_json::Value = serde_json::from_str(&game.universe_json(Some(7))).unwrap();
+        assert!(view["systems"].as_array().unwrap().is_empty());
+        assert!(view["fleets"].as_array().unwrap().is_empty());
+        assert!(game.system_view(0, Some(7)).is_none());
+
+        let omniscient: serde_json::Value =
+            serde_json::from_str(&game.universe_json(None)).unwrap();
+        assert_eq!(
+            omniscient["fleets"].as_array().unwrap().len(),
+            game.fleets().len()
+        );
+    }
 }
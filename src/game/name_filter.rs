@@ -0,0 +1,417 @@
+use std::collections::{HashMap, HashSet};
+
+/// Why a generated name was thrown away.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NameRejection {
+    Blocked { word: String },
+    AwkwardCluster { cluster: String },
+    TooSimilar { to: String },
+}
+
+/// Words no generated name may be, matched case-insensitively against each
+/// whole word of the name and its plural. These also turn up inside harmless
+/// names ("Cassia", "Titania", "Scumar"), so they are not matched as parts.
+const BLOCKLIST: &[&str] = &["ass", "cock", "cum", "dick", "fag", "nig", "rape", "tit"];
+
+/// Fragments no generated name may contain anywhere, being too long to turn
+/// up inside a harmless one by accident.
+const BLOCKED_FRAGMENTS: &[&str] = &[
+    "cunt", "fuck", "nazi", "piss", "porn", "shit", "slut", "twat", "whore",
+];
+
+/// The checks every candidate name goes through before it is used. Names
+/// with digits, like catalogue designations, skip the pronounceability and
+/// similarity checks: nobody mixes up "HD 1204" and "HD 1205".
+#[derive(Clone, Debug)]
+pub struct NameFilters {
+    /// Blocked as whole words.
+    pub blocklist: Vec<String>,
+    /// Blocked anywhere in a name.
+    pub blocked_fragments: Vec<String>,
+    /// Longest run of consonants allowed.
+    pub max_consonant_run: usize,
+    /// Names within this edit distance of a used one are rejected.
+    pub min_distance: usize,
+}
+
+const MIN_DISTANCE: usize = 1;
+
+impl Default for NameFilters {
+    fn default() -> Self {
+        Self {
+            blocklist: BLOCKLIST.iter().map(|w| w.to_string()).collect(),
+            blocked_fragments: BLOCKED_FRAGMENTS.iter().map(|w| w.to_string()).collect(),
+            max_consonant_run: 2,
+            min_distance: MIN_DISTANCE,
+        }
+    }
+}
+
+impl NameFilters {
+    pub fn check(&self, candidate: &str, used: &UsedNames) -> Result<(), NameRejection> {
+        let lower = candidate.to_lowercase();
+        if let Some(word) = self.blocked_word(&lower) {
+            return Err(NameRejection::Blocked { word });
+        }
+        if let Some(name) = used.look_alike(candidate, self.min_distance) {
+            return Err(NameRejection::TooSimilar {
+                to: name.to_string(),
+            });
+        }
+        self.check_form(candidate)
+    }
+
+    /// The checks that depend only on the candidate itself: blocked words
+    /// and awkward clusters, with no look-up against earlier names.
+    pub fn check_form(&self, candidate: &str) -> Result<(), NameRejection> {
+        let lower = candidate.to_lowercase();
+        if let Some(word) = self.blocked_word(&lower) {
+            return Err(NameRejection::Blocked { word });
+        }
+        if lower.chars().any(|c| c.is_ascii_digit()) {
+            return Ok(());
+        }
+        match consonant_run(&lower, self.max_consonant_run + 1) {
+            Some(cluster) => Err(NameRejection::AwkwardCluster { cluster }),
+            None => Ok(()),
+        }
+    }
+
+    fn blocked_word(&self, lower: &str) -> Option<String> {
+        let whole = |blocked: &str| {
+            lower.split(|c: char| !c.is_alphabetic()).any(|word| {
+                word.strip_prefix(blocked)
+                    .is_some_and(|rest| matches!(rest, "" | "s" | "es"))
+            })
+        };
+        self.blocklist
+            .iter()
+            .find(|w| whole(w))
+            .or_else(|| {
+                self.blocked_fragments
+                    .iter()
+                    .find(|w| lower.contains(w.as_str()))
+            })
+            .cloned()
+    }
+}
+
+/// Names handed out so far, indexed so the look-alike check stays cheap
+/// however many there are: by sound-alike key, and by every spelling left
+/// after deleting up to `reach` letters. Two names within edit distance `d`
+/// always share such a spelling once `d` letters are deleted from each, so
+/// only the few names filed under the candidate's own deletions need a full
+/// comparison.
+#[derive(Clone, Debug)]
+pub struct UsedNames {
+    names: HashSet<String>,
+    sounds: HashMap<String, String>,
+    deletions: HashMap<String, Vec<String>>,
+    reach: usize,
+}
+
+impl Default for UsedNames {
+    fn default() -> Self {
+        Self::with_reach(MIN_DISTANCE)
+    }
+}
+
+impl UsedNames {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An index that answers look-alike checks up to `reach` edits without
+    /// scanning every name.
+    pub fn with_reach(reach: usize) -> Self {
+        Self {
+            names: HashSet::new(),
+            sounds: HashMap::new(),
+            deletions: HashMap::new(),
+            reach,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Adds `name`, returning `false` if it was already used.
+    pub fn insert(&mut self, name: String) -> bool {
+        if self.names.contains(&name) {
+            return false;
+        }
+        if let Some(key) = sound_alike_key(&name) {
+            self.sounds.entry(key).or_insert_with(|| name.clone());
+            for spelling in deletions(&name.to_lowercase(), self.reach) {
+                self.deletions
+                    .entry(spelling)
+                    .or_default()
+                    .push(name.clone());
+            }
+        }
+        self.names.insert(name);
+        true
+    }
+
+    /// A used name `candidate` could be mistaken for: one that sounds the
+    /// same or lies within `max_distance` edits. Designations with digits in
+    /// them never look alike.
+    pub fn look_alike(&self, candidate: &str, max_distance: usize) -> Option<&str> {
+        let key = sound_alike_key(candidate)?;
+        if let Some(name) = self.sounds.get(&key) {
+            return Some(name);
+        }
+        let lower = candidate.to_lowercase();
+        let near = |name: &&String| {
+            sound_alike_key(name).is_some()
+                && within_distance(&lower, &name.to_lowercase(), max_distance)
+        };
+        if max_distance > self.reach {
+            return self.names.iter().find(near).map(String::as_str);
+        }
+        let mut spellings: Vec<String> = deletions(&lower, max_distance).into_iter().collect();
+        spellings.sort();
+        spellings
+            .iter()
+            .filter_map(|spelling| self.deletions.get(spelling))
+            .flatten()
+            .find(near)
+            .map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.names.iter()
+    }
+}
+
+impl Extend<String> for UsedNames {
+    fn extend<I: IntoIterator<Item = String>>(&mut self, names: I) {
+        for name in names {
+            self.insert(name);
+        }
+    }
+}
+
+impl FromIterator<String> for UsedNames {
+    fn from_iter<I: IntoIterator<Item = String>>(names: I) -> Self {
+        let mut used = Self::new();
+        used.extend(names);
+        used
+    }
+}
+
+/// `word` and every spelling left after deleting up to `depth` letters.
+fn deletions(word: &str, depth: usize) -> HashSet<String> {
+    let mut all = HashSet::from([word.to_string()]);
+    let mut frontier = vec![word.to_string()];
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for spelling in &frontier {
+            let chars: Vec<char> = spelling.chars().collect();
+            for skip in 0..chars.len() {
+                let shorter: String = chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != skip)
+                    .map(|(_, c)| c)
+                    .collect();
+                if all.insert(shorter.clone()) {
+                    next.push(shorter);
+                }
+            }
+        }
+        frontier = next;
+    }
+    all
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+}
+
+/// The first run of at least `len` consonant letters, if any.
+fn consonant_run(name: &str, len: usize) -> Option<String> {
+    let mut run = String::new();
+    for c in name.chars() {
+        if c.is_alphabetic() && !is_vowel(c) {
+            run.push(c);
+            if run.chars().count() >= len {
+                return Some(run);
+            }
+        } else {
+            run.clear();
+        }
+    }
+    None
+}
+
+/// A rough sound-alike key: common digraphs fold to one letter, doubled
+/// letters collapse and each vowel cluster keeps only its first vowel.
+pub fn phonetic_key(name: &str) -> String {
+    let folded = name
+        .to_lowercase()
+        .replace("ph", "f")
+        .replace("th", "t")
+        .replace("sh", "s")
+        .replace("ck", "k")
+        .replace("qu", "k")
+        .replace('c', "k")
+        .replace('z', "s")
+        .replace('x', "ks");
+    let mut key = String::new();
+    let mut last: Option<char> = None;
+    for c in folded.chars().filter(|c| c.is_alphabetic()) {
+        let repeat = last == Some(c) || (last.is_some_and(is_vowel) && is_vowel(c));
+        if !repeat {
+            key.push(c);
+        }
+        last = Some(c);
+    }
+    key
+}
+
+/// The phonetic key look-alike checks compare, or `None` for designations
+/// with digits in them.
+pub fn sound_alike_key(name: &str) -> Option<String> {
+    (!name.chars().any(|c| c.is_ascii_digit())).then(|| phonetic_key(name))
+}
+
+/// Whether the Levenshtein distance between `a` and `b` is at most `max`.
+pub fn within_distance(a: &str, b: &str, max: usize) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return false;
+    }
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != cb);
+            row[j + 1] = substitute.min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        prev = row;
+    }
+    prev[b.len()] <= max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn used(names: &[&str]) -> UsedNames {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn rejects_blocked_awkward_and_similar_names() {
+        let filters = NameFilters::default();
+        let taken = used(&["Driis", "Staisen"]);
+
+        assert_eq!(
+            filters.check("Shitaris", &taken),
+            Err(NameRejection::Blocked {
+                word: "shit".to_string()
+            })
+        );
+        assert_eq!(
+            filters.check("Kronthsar", &taken),
+            Err(NameRejection::AwkwardCluster {
+                cluster: "nth".to_string()
+            })
+        );
+        assert_eq!(
+            filters.check("Drris", &taken),
+            Err(NameRejection::TooSimilar {
+                to: "Driis".to_string()
+            })
+        );
+        assert_eq!(
+            filters.check("Staisenn", &taken),
+            Err(NameRejection::TooSimilar {
+                to: "Staisen".to_string()
+            })
+        );
+        assert_eq!(filters.check("Zaerun", &taken), Ok(()));
+        assert_eq!(filters.check("HD 4021", &used(&["HD 4022"])), Ok(()));
+    }
+
+    #[test]
+    fn short_blocked_words_only_match_whole_words() {
+        let filters = NameFilters::default();
+        let none = used(&[]);
+        for name in ["Cassia", "Titania", "Scumar", "Nigeth", "Assar", "Dickenos"] {
+            assert_eq!(filters.check(name, &none), Ok(()), "{name}");
+        }
+        for (name, word) in [("Tit", "tit"), ("Asses", "ass"), ("Cum Aris", "cum")] {
+            assert_eq!(
+                filters.check_form(name),
+                Err(NameRejection::Blocked {
+                    word: word.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn the_index_finds_every_look_alike_a_scan_would() {
+        let names = [
+            "Kaetheion",
+            "Kronaen",
+            "Velora",
+            "Staisen",
+            "Driis",
+            "Mossar",
+            "HD 4021",
+        ];
+        let candidates = [
+            "Ketheion", "Pronaen", "Kronaena", "Ronaen", "Veloar", "Vellora", "Stasen", "Driiss",
+            "Mosar", "Zaerun", "Lorvane", "HD 4022",
+        ];
+        for reach in [1, 2] {
+            let mut index = UsedNames::with_reach(reach);
+            index.extend(names.iter().map(|n| n.to_string()));
+            for candidate in candidates {
+                for max in 1..=3 {
+                    let scan = sound_alike_key(candidate).and_then(|key| {
+                        names.iter().find(|n| {
+                            sound_alike_key(n).is_some_and(|k| {
+                                k == key
+                                    || within_distance(
+                                        &candidate.to_lowercase(),
+                                        &n.to_lowercase(),
+                                        max,
+                                    )
+                            })
+                        })
+                    });
+                    assert_eq!(
+                        index.look_alike(candidate, max).is_some(),
+                        scan.is_some(),
+                        "{candidate} within {max} at reach {reach}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn phonetic_keys_fold_sound_alikes() {
+        assert_eq!(phonetic_key("Driis"), phonetic_key("Drris"));
+        assert_eq!(phonetic_key("Phaeron"), phonetic_key("Faron"));
+        assert_eq!(phonetic_key("Zaerun"), phonetic_key("Saerun"));
+        assert_ne!(phonetic_key("Voenm"), phonetic_key("Zaerun"));
+        assert!(within_distance("kitten", "sitten", 1));
+        assert!(!within_distance("kitten", "sitting", 2));
+        assert!(within_distance("kitten", "sitting", 3));
+    }
+}
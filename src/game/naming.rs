@@ -1,7 +1,13 @@
 use crate::game::hazard::HazardKind;
-use rand::Rng;
+use crate::game::name_filter::{NameFilters, UsedNames};
+use crate::game::nickname::{self, Locale, ENGLISH};
+use crate::game::replay::Checksum;
+use crate::game::system::{OrbitalBody, OrbitalKind, StarSystem};
+use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
 
 const NAME_ONSETS: &[&str] = &[
     "st", "dr", "kr", "m", "n", "v", "th", "z", "gl", "pr", "t", "k", "r", "s", "l",
@@ -15,244 +21,962 @@ fn pick<'a>(rng: &mut ChaCha8Rng, options: &'a [&str]) -> &'a str {
     options[idx]
 }
 
-fn build_star_name_candidate(rng: &mut ChaCha8Rng) -> String {
+/// Sound inventory for one naming culture.
+pub struct Phonology {
+    onsets: &'static [&'static str],
+    vowels: &'static [&'static str],
+    codas: &'static [&'static str],
+    endings: &'static [&'static str],
+}
+
+const CLASSIC: Phonology = Phonology {
+    onsets: NAME_ONSETS,
+    vowels: NAME_VOWELS,
+    codas: NAME_CODAS,
+    endings: NAME_ENDINGS,
+};
+
+const HARSH: Phonology = Phonology {
+    onsets: &["k", "kr", "gr", "dr", "z", "zh", "th", "v", "br", "g", "x"],
+    vowels: &["a", "o", "u", "ae", "au"],
+    codas: &["k", "rk", "x", "g", "z", "th", "rn", "sk"],
+    endings: &["ak", "ox", "ur", "ag", "ek", "oth", "uz"],
+};
+
+const FLOWING: Phonology = Phonology {
+    onsets: &["l", "s", "m", "n", "y", "r", "f", "ph", "sh", "el"],
+    vowels: &["a", "e", "i", "ia", "ae", "ie", "ea", "io"],
+    codas: &["l", "n", "r", "s", "ll"],
+    endings: &["ia", "elle", "ine", "ara", "is", "ea", "wyn"],
+};
+
+const LATINATE: Phonology = Phonology {
+    onsets: &["c", "v", "t", "p", "l", "m", "qu", "s", "f", "aur"],
+    vowels: &["a", "e", "i", "o", "u"],
+    codas: &["r", "l", "n", "s", "t"],
+    endings: &["us", "um", "ia", "ae", "ix", "or", "ium", "ensis"],
+};
+
+const CATALOGUES: &[&str] = &["HD", "HIP", "GJ", "KIC", "TYC"];
+const CATALOGUE_NUMBERS: Range<u64> = 1000..100_000;
+
+/// The slots of one name shape, in spelling order. `phonology_candidate`
+/// draws these shapes at random; the enumeration below walks them in order.
+#[derive(Clone, Copy)]
+enum Slot {
+    Onset,
+    Vowel,
+    Coda,
+    Ending,
+}
+
+const SHAPES: [&[Slot]; 4] = [
+    &[Slot::Onset, Slot::Vowel, Slot::Ending],
+    &[Slot::Onset, Slot::Vowel, Slot::Coda, Slot::Ending],
+    &[
+        Slot::Onset,
+        Slot::Vowel,
+        Slot::Onset,
+        Slot::Vowel,
+        Slot::Ending,
+    ],
+    &[Slot::Onset, Slot::Vowel, Slot::Ending, Slot::Coda],
+];
+
+impl Phonology {
+    fn slot(&self, slot: Slot) -> &'static [&'static str] {
+        match slot {
+            Slot::Onset => self.onsets,
+            Slot::Vowel => self.vowels,
+            Slot::Coda => self.codas,
+            Slot::Ending => self.endings,
+        }
+    }
+
+    fn shape_size(&self, shape: &[Slot]) -> u64 {
+        shape.iter().map(|s| self.slot(*s).len() as u64).product()
+    }
+
+    /// How many spellings the shapes can produce, counting repeats.
+    fn size(&self) -> u64 {
+        SHAPES.iter().map(|shape| self.shape_size(shape)).sum()
+    }
+
+    /// The spelling numbered `index`: shapes in order, then each slot as a
+    /// digit with the last slot varying fastest.
+    fn name_at(&self, mut index: u64) -> Option<String> {
+        for shape in SHAPES {
+            let size = self.shape_size(shape);
+            if index >= size {
+                index -= size;
+                continue;
+            }
+            let mut parts = Vec::with_capacity(shape.len());
+            for slot in shape.iter().rev() {
+                let table = self.slot(*slot);
+                parts.push(table[(index % table.len() as u64) as usize]);
+                index /= table.len() as u64;
+            }
+            parts.reverse();
+            return Some(parts.concat());
+        }
+        None
+    }
+
+    /// The lowest index that spells `name`. Different slot choices can spell
+    /// the same word ("ia" + "r" and "i" + "ar"), so only the lowest counts.
+    fn first_index_of(&self, name: &str) -> Option<u64> {
+        let mut offset = 0;
+        for shape in SHAPES {
+            if let Some(index) = self.parse(shape, name, 0) {
+                return Some(offset + index);
+            }
+            offset += self.shape_size(shape);
+        }
+        None
+    }
+
+    fn parse(&self, shape: &[Slot], rest: &str, index: u64) -> Option<u64> {
+        let Some((slot, tail)) = shape.split_first() else {
+            return rest.is_empty().then_some(index);
+        };
+        let table = self.slot(*slot);
+        table
+            .iter()
+            .enumerate()
+            .filter(|(_, part)| rest.starts_with(**part))
+            .filter_map(|(digit, part)| {
+                self.parse(
+                    tail,
+                    &rest[part.len()..],
+                    index * table.len() as u64 + digit as u64,
+                )
+            })
+            .min()
+    }
+}
+
+fn phonology_candidate(rng: &mut ChaCha8Rng, p: &Phonology) -> String {
     // A few hand-rolled phoneme patterns to keep names pronounceable.
     match rng.gen_range(0..4) {
         0 => format!(
             "{}{}{}",
-            pick(rng, NAME_ONSETS),
-            pick(rng, NAME_VOWELS),
-            pick(rng, NAME_ENDINGS)
+            pick(rng, p.onsets),
+            pick(rng, p.vowels),
+            pick(rng, p.endings)
         ),
         1 => format!(
             "{}{}{}{}",
-            pick(rng, NAME_ONSETS),
-            pick(rng, NAME_VOWELS),
-            pick(rng, NAME_CODAS),
-            pick(rng, NAME_ENDINGS)
+            pick(rng, p.onsets),
+            pick(rng, p.vowels),
+            pick(rng, p.codas),
+            pick(rng, p.endings)
         ),
         2 => format!(
             "{}{}{}{}{}",
-            pick(rng, NAME_ONSETS),
-            pick(rng, NAME_VOWELS),
-            pick(rng, NAME_ONSETS),
-            pick(rng, NAME_VOWELS),
-            pick(rng, NAME_ENDINGS)
+            pick(rng, p.onsets),
+            pick(rng, p.vowels),
+            pick(rng, p.onsets),
+            pick(rng, p.vowels),
+            pick(rng, p.endings)
         ),
         _ => format!(
             "{}{}{}{}",
-            pick(rng, NAME_ONSETS),
-            pick(rng, NAME_VOWELS),
-            pick(rng, NAME_ENDINGS),
-            pick(rng, NAME_CODAS)
+            pick(rng, p.onsets),
+            pick(rng, p.vowels),
+            pick(rng, p.endings),
+            pick(rng, p.codas)
         ),
     }
 }
 
-pub fn generate_star_name(rng: &mut ChaCha8Rng, used: &mut HashSet<String>) -> String {
-    for _ in 0..500 {
-        let candidate = build_star_name_candidate(rng);
-        let mut chars = candidate.chars();
-        let capitalized = match chars.next() {
-            Some(first) => format!("{}{}", first.to_ascii_uppercase(), chars.collect::<String>()),
-            None => continue,
+/// How a part of the galaxy names its stars.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameStyle {
+    /// The original mixed phoneme tables.
+    #[default]
+    Classic,
+    Harsh,
+    Flowing,
+    Latinate,
+    /// Survey designations such as "HD 12345".
+    Catalogue,
+    Markov(MarkovModel),
+}
+
+impl NameStyle {
+    pub fn candidate(&self, rng: &mut ChaCha8Rng) -> String {
+        match self {
+            NameStyle::Classic => phonology_candidate(rng, &CLASSIC),
+            NameStyle::Harsh => phonology_candidate(rng, &HARSH),
+            NameStyle::Flowing => phonology_candidate(rng, &FLOWING),
+            NameStyle::Latinate => phonology_candidate(rng, &LATINATE),
+            NameStyle::Catalogue => format!(
+                "{} {}",
+                pick(rng, CATALOGUES),
+                rng.gen_range(CATALOGUE_NUMBERS)
+            ),
+            NameStyle::Markov(model) => model.generate(rng),
+        }
+    }
+
+    fn phonology(&self) -> Option<&'static Phonology> {
+        match self {
+            NameStyle::Classic => Some(&CLASSIC),
+            NameStyle::Harsh => Some(&HARSH),
+            NameStyle::Flowing => Some(&FLOWING),
+            NameStyle::Latinate => Some(&LATINATE),
+            NameStyle::Catalogue | NameStyle::Markov(_) => None,
+        }
+    }
+
+    /// How many names the style can spell, counting any spelled twice. A
+    /// Markov style counts none here: its names are indexed by the
+    /// `MarkovSpace` that `MarkovModel::space` builds, once.
+    pub fn space_size(&self) -> u64 {
+        match self {
+            NameStyle::Catalogue => {
+                CATALOGUES.len() as u64 * (CATALOGUE_NUMBERS.end - CATALOGUE_NUMBERS.start)
+            }
+            NameStyle::Markov(_) => 0,
+            style => style.phonology().map_or(0, Phonology::size),
+        }
+    }
+
+    /// The name numbered `index`, if that index spells a name no lower index
+    /// also spells. Catalogue designations are always distinct; Markov words
+    /// come from the style's `MarkovSpace` instead.
+    pub fn name_at(&self, index: u64) -> Option<String> {
+        match self {
+            NameStyle::Catalogue => {
+                let per_prefix = CATALOGUE_NUMBERS.end - CATALOGUE_NUMBERS.start;
+                let prefix = CATALOGUES.get((index / per_prefix) as usize)?;
+                Some(format!(
+                    "{} {}",
+                    prefix,
+                    CATALOGUE_NUMBERS.start + index % per_prefix
+                ))
+            }
+            NameStyle::Markov(_) => None,
+            style => {
+                let phonology = style.phonology()?;
+                let name = phonology.name_at(index)?;
+                (phonology.first_index_of(&name) == Some(index)).then_some(name)
+            }
+        }
+    }
+}
+
+/// A keyed shuffle of `0..len`. A four-round Feistel network permutes the
+/// smallest even power of two covering `len`, and indices that land outside
+/// the range walk the cycle until they come back in, which takes under four
+/// steps on average.
+#[derive(Clone, Debug)]
+pub struct Shuffle {
+    len: u64,
+    half_bits: u32,
+    key: u64,
+}
+
+impl Shuffle {
+    pub fn new(len: u64, key: u64) -> Self {
+        let mut half_bits = 0;
+        while (1u128 << (2 * half_bits)) < len as u128 {
+            half_bits += 1;
+        }
+        Self {
+            len,
+            half_bits,
+            key,
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Where `index` lands; a bijection on `0..len`.
+    pub fn at(&self, index: u64) -> u64 {
+        let mut x = index;
+        loop {
+            x = self.round_trip(x);
+            if x < self.len {
+                return x;
+            }
+        }
+    }
+
+    fn round_trip(&self, x: u64) -> u64 {
+        let mask = (1u64 << self.half_bits) - 1;
+        let (mut left, mut right) = (x >> self.half_bits, x & mask);
+        for round in 0..4u64 {
+            let f = splitmix(self.key ^ round.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ right) & mask;
+            (left, right) = (right, left ^ f);
+        }
+        (left << self.half_bits) | right
+    }
+}
+
+fn splitmix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Hands out every name a style can spell, once each, in an order shuffled
+/// by `key`, so no name is ever drawn twice and retried. Spellings that fail
+/// the filters are stepped over: blocked words, awkward clusters, spellings a
+/// lower index already has, and anything that looks like a name in `used`.
+/// Once the style runs dry the sequence carries on with catalogue
+/// designations, which never look alike, so there is always a next name.
+/// Callers pass the same `used` index every time.
+#[derive(Clone, Debug)]
+pub struct NameSequence {
+    style: NameStyle,
+    markov: Option<MarkovSpace>,
+    shuffle: Shuffle,
+    next: u64,
+    key: u64,
+    fallback: Option<Box<NameSequence>>,
+    /// Designations numbered past `CATALOGUE_NUMBERS`, for a catalogue that
+    /// has run out too.
+    overflow: u64,
+    filters: NameFilters,
+}
+
+impl NameSequence {
+    pub fn new(style: NameStyle, key: u64) -> Self {
+        let markov = match &style {
+            NameStyle::Markov(model) => Some(model.space()),
+            _ => None,
         };
+        let len = markov
+            .as_ref()
+            .map_or_else(|| style.space_size(), MarkovSpace::len);
+        Self {
+            style,
+            markov,
+            shuffle: Shuffle::new(len, key),
+            next: 0,
+            key,
+            fallback: None,
+            overflow: 0,
+            filters: NameFilters::default(),
+        }
+    }
+
+    pub fn style(&self) -> &NameStyle {
+        &self.style
+    }
 
-        if used.insert(capitalized.clone()) {
-            return capitalized;
+    /// The next name that passes the filters against `used`, which it is
+    /// then added to.
+    pub fn next_name(&mut self, used: &mut UsedNames) -> String {
+        while self.next < self.shuffle.len() {
+            let index = self.shuffle.at(self.next);
+            self.next += 1;
+            let spelling = match &self.markov {
+                Some(space) => space.name_at(index),
+                None => self.style.name_at(index),
+            };
+            let Some(name) = spelling.map(|n| capitalise(&n)) else {
+                continue;
+            };
+            if name.is_empty() || used.contains(&name) || self.filters.check(&name, used).is_err() {
+                continue;
+            }
+            used.insert(name.clone());
+            return name;
+        }
+
+        if self.style == NameStyle::Catalogue {
+            loop {
+                let name = format!(
+                    "{} {}",
+                    CATALOGUES[0],
+                    CATALOGUE_NUMBERS.end + self.overflow
+                );
+                self.overflow += 1;
+                if used.insert(name.clone()) {
+                    return name;
+                }
+            }
         }
+        let key = splitmix(self.key);
+        self.fallback
+            .get_or_insert_with(|| Box::new(NameSequence::new(NameStyle::Catalogue, key)))
+            .next_name(used)
     }
+}
 
-    panic!("exhausted attempts to generate a unique star name");
-}
-
-const ARTICLES: &[&str] = &["The", "A", "This", "That"];
-const ADJECTIVES: &[&str] = &[
-    "Silent",
-    "Vagrant",
-    "Crimson",
-    "Iron",
-    "Glass",
-    "Blue",
-    "Fallen",
-    "Wandering",
-    "Hidden",
-    "Verdant",
-    "Ashen",
-    "Amber",
-    "Sable",
-    "Gilded",
-    "Fractured",
-    "Distant",
-    "Last",
-    "First",
-    "Forgotten",
-    "Radiant",
-    "Cold",
-    "Crowned",
-    "Broken",
-    "Lonely",
-    "Burning",
-    "Restless",
-    "Sleeping",
-    "Shattered",
-    "Veiled",
-    "Northern",
-    "Southern",
-    "Eastern",
-    "Western",
-    "Drifting",
-    "Silver",
-];
-const NOUNS: &[&str] = &[
-    "Garden",
-    "Anvil",
-    "Wake",
-    "Halo",
-    "Drifter",
-    "Chorus",
-    "Spire",
-    "Tide",
-    "Beacon",
-    "Crown",
-    "Forge",
-    "Harbor",
-    "Passage",
-    "Pilgrim",
-    "Pilgrimage",
-    "Whisper",
-    "Ember",
-    "Comet",
-    "Siren",
-    "Step",
-    "Gate",
-    "Veil",
-    "Crossing",
-    "Hearth",
-    "Dawn",
-    "Dusk",
-    "Eclipse",
-    "Bridge",
-    "Hollow",
-    "Gulf",
-    "Ridge",
-    "Memory",
-];
-const VERBS: &[&str] = &[
-    "Waits",
-    "Sleeps",
-    "Echoes",
-    "Burns",
-    "Drifts",
-    "Remains",
-    "Flickers",
-    "Stands",
-    "Watches",
-    "Fades",
-];
+/// One `NameSequence` per distinct style in a `NamingConfig`, each keyed by
+/// the style and a shared key, so every region or faction with the same
+/// style draws from one shuffle. Styles are matched up once, when the config
+/// is read, and a sequence is only built once something is drawn from it.
+#[derive(Clone, Debug)]
+pub struct StyleSequences {
+    key: u64,
+    styles: Vec<NameStyle>,
+    sequences: Vec<Option<NameSequence>>,
+    default: usize,
+    regions: Vec<(Range<u32>, usize)>,
+    factions: Vec<usize>,
+}
 
-fn maybe<'a>(rng: &mut ChaCha8Rng, options: &'a [&str], chance: f64) -> Option<&'a str> {
-    (rng.gen::<f64>() < chance).then(|| pick(rng, options))
+impl StyleSequences {
+    pub fn new(config: &NamingConfig, key: u64) -> Self {
+        let mut styles: Vec<NameStyle> = Vec::new();
+        let mut slot = |style: &NameStyle| match styles.iter().position(|s| s == style) {
+            Some(idx) => idx,
+            None => {
+                styles.push(style.clone());
+                styles.len() - 1
+            }
+        };
+        let default = slot(&config.default);
+        let regions = config
+            .regions
+            .iter()
+            .map(|(range, style)| (range.clone(), slot(style)))
+            .collect();
+        let factions = config.factions.iter().map(&mut slot).collect();
+        Self {
+            key,
+            sequences: vec![None; styles.len()],
+            styles,
+            default,
+            regions,
+            factions,
+        }
+    }
+
+    /// The next name in `NamingConfig::system_style(system)`.
+    pub fn next_system_name(&mut self, system: u32, used: &mut UsedNames) -> String {
+        let slot = self
+            .regions
+            .iter()
+            .find(|(range, _)| range.contains(&system))
+            .map_or(self.default, |(_, slot)| *slot);
+        self.next_name(slot, used)
+    }
+
+    /// The next name in `NamingConfig::faction_style(faction)`.
+    pub fn next_faction_name(&mut self, faction: u32, used: &mut UsedNames) -> String {
+        let slot = self
+            .factions
+            .get(faction as usize)
+            .copied()
+            .unwrap_or(self.default);
+        self.next_name(slot, used)
+    }
+
+    fn next_name(&mut self, slot: usize, used: &mut UsedNames) -> String {
+        let style = &self.styles[slot];
+        let key = self.key;
+        self.sequences[slot]
+            .get_or_insert_with(|| {
+                let key = Checksum::of(format!("{:?}", style).as_bytes()).0 ^ key;
+                NameSequence::new(style.clone(), key)
+            })
+            .next_name(used)
+    }
 }
 
-fn themed_adjective(rng: &mut ChaCha8Rng, hazards: &[HazardKind]) -> Option<&'static str> {
-    let themed: &[&str] = match hazards.iter().next() {
-        Some(HazardKind::Radiation) => &["Irradiated", "Ionic", "Radiant", "Searing"],
-        Some(HazardKind::Pirates) => &["Corsair", "Rogue", "Scarred", "Bloodied"],
-        Some(HazardKind::Debris) => &["Shattered", "Broken", "Sundered", "Twisted"],
-        None => &[],
-    };
-    if !themed.is_empty() && rng.gen::<f64>() < 0.35 {
-        return Some(pick(rng, themed));
+fn capitalise(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}", first.to_ascii_uppercase(), chars.as_str()),
+        None => String::new(),
     }
-    Some(pick(rng, ADJECTIVES))
 }
 
-fn themed_noun(rng: &mut ChaCha8Rng, hazards: &[HazardKind]) -> Option<&'static str> {
-    let themed: &[&str] = match hazards.iter().next() {
-        Some(HazardKind::Radiation) => &["Flare", "Pulse", "Glow"],
-        Some(HazardKind::Pirates) => &["Cutlass", "Raid", "Corsair", "Marauder"],
-        Some(HazardKind::Debris) => &["Wreck", "Shard", "Graveyard"],
-        None => &[],
-    };
-    if !themed.is_empty() && rng.gen::<f64>() < 0.35 {
-        return Some(pick(rng, themed));
-    }
-    Some(pick(rng, NOUNS))
-}
-
-pub fn generate_nickname(
-    rng: &mut ChaCha8Rng,
-    used: &mut HashSet<String>,
-    hazards: &[HazardKind],
-) -> Option<String> {
-    #[derive(Clone, Copy)]
-    enum Token {
-        Article,
-        Adjective,
-        Noun,
-        Verb,
-    }
-
-    const PATTERNS: &[(&[Token], u32)] = &[
-        (&[Token::Noun], 4),
-        (&[Token::Adjective, Token::Noun], 4),
-        (&[Token::Article, Token::Noun], 3),
-        (&[Token::Article, Token::Adjective, Token::Noun], 2),
-        (&[Token::Adjective, Token::Adjective, Token::Noun], 1),
-        (&[Token::Article, Token::Noun, Token::Verb], 1),
-        (&[Token::Adjective, Token::Noun, Token::Verb], 1),
-        (&[Token::Article, Token::Adjective, Token::Noun, Token::Verb], 1),
-    ];
+const MARKOV_START: char = '^';
+const MARKOV_END: char = '$';
+const MARKOV_MIN_LEN: usize = 3;
+const MARKOV_MAX_LEN: usize = 12;
 
-    let total_weight: u32 = PATTERNS.iter().map(|(_, w)| w).sum();
+/// A character-level Markov chain trained on example names. Each run of
+/// `order` letters maps to the letters seen after it, with counts.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MarkovModel {
+    order: usize,
+    transitions: BTreeMap<String, Vec<(char, u32)>>,
+}
 
-    for _ in 0..120 {
-        let mut roll = rng.gen_range(0..total_weight);
-        let mut chosen: &[Token] = PATTERNS[0].0;
-        for (tokens, weight) in PATTERNS {
-            if roll < *weight {
-                chosen = tokens;
-                break;
+impl MarkovModel {
+    pub fn train<S: AsRef<str>>(words: &[S], order: usize) -> Self {
+        let order = order.max(1);
+        let mut counts: BTreeMap<String, BTreeMap<char, u32>> = BTreeMap::new();
+        for word in words {
+            let word = word.as_ref().trim().to_lowercase();
+            if word.is_empty() {
+                continue;
+            }
+            let chars: Vec<char> = std::iter::repeat_n(MARKOV_START, order)
+                .chain(word.chars())
+                .chain(std::iter::once(MARKOV_END))
+                .collect();
+            for window in chars.windows(order + 1) {
+                let context: String = window[..order].iter().collect();
+                *counts
+                    .entry(context)
+                    .or_default()
+                    .entry(window[order])
+                    .or_default() += 1;
             }
-            roll -= *weight;
         }
+        let transitions = counts
+            .into_iter()
+            .map(|(context, next)| (context, next.into_iter().collect()))
+            .collect();
+        Self { order, transitions }
+    }
 
-        let mut parts = Vec::new();
-        for token in chosen {
-            match token {
-                Token::Article => {
-                    if let Some(article) = maybe(rng, ARTICLES, 0.85) {
-                        parts.push(article);
-                    }
+    fn next_char(&self, rng: &mut ChaCha8Rng, context: &str) -> Option<char> {
+        let options = self.transitions.get(context)?;
+        let total: u32 = options.iter().map(|(_, n)| n).sum();
+        let mut roll = rng.gen_range(0..total);
+        for (c, n) in options {
+            if roll < *n {
+                return Some(*c);
+            }
+            roll -= n;
+        }
+        None
+    }
+
+    /// Walks the chain from the start of a word, rolling again (a few
+    /// times) when it tries to end before `MARKOV_MIN_LEN` letters. An
+    /// untrained model yields an empty string.
+    pub fn generate(&self, rng: &mut ChaCha8Rng) -> String {
+        let mut word = String::new();
+        let mut context: String = std::iter::repeat_n(MARKOV_START, self.order).collect();
+        let mut early_ends = 0;
+        while word.chars().count() < MARKOV_MAX_LEN {
+            match self.next_char(rng, &context) {
+                Some(MARKOV_END) if word.chars().count() >= MARKOV_MIN_LEN || early_ends >= 8 => {
+                    break
                 }
-                Token::Adjective => {
-                    if let Some(adj) = themed_adjective(rng, hazards) {
-                        parts.push(adj);
-                    }
+                Some(MARKOV_END) => early_ends += 1,
+                Some(c) => {
+                    word.push(c);
+                    context.remove(0);
+                    context.push(c);
                 }
-                Token::Noun => {
-                    if let Some(noun) = themed_noun(rng, hazards) {
-                        parts.push(noun);
-                    }
+                None => break,
+            }
+        }
+        word
+    }
+
+    /// Every word the chain can spell, numbered.
+    pub fn space(&self) -> MarkovSpace {
+        let mut space = MarkovSpace {
+            model: self.clone(),
+            completions: HashMap::new(),
+        };
+        space.count(&space.start(), 0);
+        space
+    }
+}
+
+/// Every word a `MarkovModel` can spell from the start of a word to its end
+/// in `MARKOV_MIN_LEN..=MARKOV_MAX_LEN` letters, numbered by taking each
+/// context's options in order. Each step counts the words left behind every
+/// option, so any number maps straight to its word.
+#[derive(Clone, Debug)]
+pub struct MarkovSpace {
+    model: MarkovModel,
+    /// Words that can still be finished from a context with this many
+    /// letters spelled, saturating at `u64::MAX`.
+    completions: HashMap<(String, usize), u64>,
+}
+
+impl MarkovSpace {
+    fn start(&self) -> String {
+        std::iter::repeat_n(MARKOV_START, self.model.order).collect()
+    }
+
+    /// Words behind choosing `next` after `context`, `len` letters in.
+    fn behind(&self, context: &str, next: char, len: usize) -> u64 {
+        match next {
+            MARKOV_END => u64::from((MARKOV_MIN_LEN..=MARKOV_MAX_LEN).contains(&len)),
+            _ if len < MARKOV_MAX_LEN => self
+                .completions
+                .get(&(shift(context, next), len + 1))
+                .copied()
+                .unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    fn count(&mut self, context: &str, len: usize) -> u64 {
+        if let Some(&n) = self.completions.get(&(context.to_string(), len)) {
+            return n;
+        }
+        let options = self
+            .model
+            .transitions
+            .get(context)
+            .cloned()
+            .unwrap_or_default();
+        let mut total: u64 = 0;
+        for (next, _) in options {
+            if next != MARKOV_END && len < MARKOV_MAX_LEN {
+                self.count(&shift(context, next), len + 1);
+            }
+            total = total.saturating_add(self.behind(context, next, len));
+        }
+        self.completions.insert((context.to_string(), len), total);
+        total
+    }
+
+    pub fn len(&self) -> u64 {
+        self.completions
+            .get(&(self.start(), 0))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn name_at(&self, mut index: u64) -> Option<String> {
+        let mut context = self.start();
+        let mut word = String::new();
+        loop {
+            let len = word.chars().count();
+            let options = self.model.transitions.get(&context)?;
+            let mut chosen = None;
+            for (next, _) in options {
+                let behind = self.behind(&context, *next, len);
+                if index < behind {
+                    chosen = Some(*next);
+                    break;
                 }
-                Token::Verb => {
-                    if let Some(verb) = maybe(rng, VERBS, 0.35) {
-                        parts.push(verb);
-                    }
+                index -= behind;
+            }
+            match chosen? {
+                MARKOV_END => return Some(word),
+                next => {
+                    word.push(next);
+                    context = shift(&context, next);
                 }
             }
         }
+    }
+}
 
-        if parts.is_empty() {
-            continue;
+/// `context` with its first letter dropped and `next` added.
+fn shift(context: &str, next: char) -> String {
+    context
+        .chars()
+        .skip(1)
+        .chain(std::iter::once(next))
+        .collect()
+}
+
+/// Picks a naming style per faction and per stretch of system ids.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NamingConfig {
+    pub default: NameStyle,
+    /// Systems whose id falls in a range take that range's style; the first
+    /// matching range wins.
+    pub regions: Vec<(Range<u32>, NameStyle)>,
+    /// Faction `n` takes `factions[n]`, falling back to `default`.
+    pub factions: Vec<NameStyle>,
+    /// The language nicknames are written in.
+    pub nicknames: Locale,
+}
+
+impl NamingConfig {
+    pub fn system_style(&self, system: u32) -> &NameStyle {
+        self.regions
+            .iter()
+            .find(|(range, _)| range.contains(&system))
+            .map_or(&self.default, |(_, style)| style)
+    }
+
+    pub fn faction_style(&self, faction: u32) -> &NameStyle {
+        self.factions.get(faction as usize).unwrap_or(&self.default)
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+pub(crate) fn maybe<'a>(rng: &mut ChaCha8Rng, options: &'a [&str], chance: f64) -> Option<&'a str> {
+    (rng.gen::<f64>() < chance).then(|| pick(rng, options))
+}
+
+/// English themed adjective for narration; see `nickname::themed_adjective`.
+pub(crate) fn themed_adjective(rng: &mut ChaCha8Rng, hazards: &[HazardKind]) -> &'static str {
+    nickname::themed_adjective(rng, &ENGLISH, hazards).forms[0]
+}
+
+/// English themed noun for narration; see `nickname::themed_noun`.
+pub(crate) fn themed_noun(rng: &mut ChaCha8Rng, hazards: &[HazardKind]) -> &'static str {
+    nickname::themed_noun(rng, &ENGLISH, hazards).word
+}
+
+/// Rules for the lore grammar. A `{symbol}` in a line expands to one of the
+/// symbol's lines, picked at random and expanded in turn; symbols with no
+/// rule are filled from the body itself.
+const LORE: &[(&str, &[&str])] = &[
+    (
+        "body",
+        &[
+            "{A_tone} {kind}{called}. {detail}",
+            "{name} is {a_tone} {kind}{called}. {detail}",
+            "{A_tone} {kind}, {distance}.{called_again} {detail}",
+        ],
+    ),
+    (
+        "far",
+        &[
+            "far out where the light thins",
+            "on a long, slow orbit",
+            "at the edge of its star's reach",
+        ],
+    ),
+    (
+        "near",
+        &[
+            "close enough to feel its star's heat",
+            "on a quick, tight orbit",
+        ],
+    ),
+    ("moon_far", &["on a wide, lazy orbit", "far from its world"]),
+    (
+        "moon_near",
+        &["hugging its world", "close enough to skim its world's sky"],
+    ),
+    (
+        "calm_tone",
+        &[
+            "quiet",
+            "pale",
+            "windswept",
+            "lonely",
+            "cold",
+            "unremarkable",
+        ],
+    ),
+    ("radiation_tone", &["scorched", "glowing", "sun-blasted"]),
+    ("pirates_tone", &["scarred", "lawless", "half-hidden"]),
+    ("debris_tone", &["battered", "cratered", "ring-choked"]),
+    (
+        "calm_detail",
+        &[
+            "Survey crews have found little to fear here.",
+            "Nothing stirs on it but dust.",
+            "It waits, unclaimed, for the first prospector.",
+        ],
+    ),
+    (
+        "radiation_detail",
+        &[
+            "Ion storms sweep it without warning.",
+            "Instruments burn out within hours of landing.",
+            "Its {shadow} glow faintly on the night side.",
+        ],
+    ),
+    (
+        "pirates_detail",
+        &[
+            "Corsairs shelter in its {shadow}.",
+            "Raiders run dark among its {shadow}, waiting for easy prey.",
+            "Beacons here have a way of going quiet.",
+        ],
+    ),
+    (
+        "debris_detail",
+        &[
+            "Wreckage from an older age drifts in its orbit.",
+            "Shattered rock circles it like a broken crown.",
+            "Pilots thread its {shadow} at their peril.",
+        ],
+    ),
+    ("shadow", &["craters", "shadows", "ravines", "canyons"]),
+    (
+        "system",
+        &[
+            "{stars} {orbit} {worlds}{belts}.",
+            "Here {stars_lower} {orbit} {worlds}{belts}.",
+        ],
+    ),
+    (
+        "one_star",
+        &["watches over", "holds", "shepherds", "lights"],
+    ),
+    ("many_stars", &["watch over", "hold", "shepherd", "light"]),
+];
+
+fn rule(symbol: &str) -> Option<&'static [&'static str]> {
+    LORE.iter()
+        .find(|(s, _)| *s == symbol)
+        .map(|(_, lines)| *lines)
+}
+
+/// Expands `line` against `LORE` and `vars`, at most a few rules deep.
+fn expand(rng: &mut ChaCha8Rng, line: &str, vars: &[(&str, String)], depth: u32) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        let symbol = &rest[open + 1..open + close];
+        if let Some((_, value)) = vars.iter().find(|(name, _)| *name == symbol) {
+            out.push_str(value);
+        } else if let Some(lines) = rule(symbol).filter(|_| depth < 4) {
+            let line = pick(rng, lines);
+            out.push_str(&expand(rng, line, vars, depth + 1));
         }
+        rest = &rest[open + close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
 
-        let nickname = parts.join(" ");
-        if used.insert(nickname.clone()) {
-            return Some(nickname);
+fn lore_rng(key: &str) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(Checksum::of(key.as_bytes()).0)
+}
+
+fn with_article(word: &str) -> String {
+    let article = if word.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        "an"
+    } else {
+        "a"
+    };
+    format!("{} {}", article, word)
+}
+
+fn number_word(n: usize) -> String {
+    const WORDS: &[&str] = &[
+        "no", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    WORDS
+        .get(n)
+        .map_or_else(|| n.to_string(), |w| w.to_string())
+}
+
+/// A sentence or two of flavour for `body`. Only `hazards` and `nickname`
+/// colour it, so callers pass an empty list and `None` until the body has
+/// been surveyed. The same body and hazards always read the same way.
+pub fn describe_body(body: &OrbitalBody, hazards: &[HazardKind], nickname: Option<&str>) -> String {
+    let mut kinds: Vec<HazardKind> = Vec::new();
+    for kind in hazards {
+        if !kinds.contains(kind) {
+            kinds.push(*kind);
         }
     }
+    let mut rng = lore_rng(&format!("{}#{}#{:?}", body.id, body.name, kinds));
+
+    let symbol = |kind: HazardKind| match kind {
+        HazardKind::Radiation => "radiation",
+        HazardKind::Pirates => "pirates",
+        HazardKind::Debris => "debris",
+    };
+    let tone_rule = match kinds.as_slice() {
+        [] => "calm_tone".to_string(),
+        _ => format!("{}_tone", symbol(kinds[rng.gen_range(0..kinds.len())])),
+    };
+    let tone = expand(&mut rng, &format!("{{{}}}", tone_rule), &[], 0);
+    let detail = if kinds.is_empty() {
+        expand(&mut rng, "{calm_detail}", &[], 0)
+    } else {
+        kinds
+            .iter()
+            .map(|k| expand(&mut rng, &format!("{{{}_detail}}", symbol(*k)), &[], 0))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let kind = match body.kind {
+        OrbitalKind::Planetoid => "world",
+        OrbitalKind::AsteroidBelt => "belt of rock",
+        OrbitalKind::Moon => "moon",
+    };
+    let called = nickname.map_or_else(String::new, |n| format!(" that spacers call {}", n));
+    let called_again = nickname.map_or_else(String::new, |n| format!(" Spacers call it {}.", n));
+    let a_tone = with_article(&tone);
+    let near = match body.kind {
+        OrbitalKind::Planetoid => body.distance < 150,
+        OrbitalKind::AsteroidBelt => body.distance < 450,
+        OrbitalKind::Moon => body.distance < 8,
+    };
+    let distance = match (&body.kind, near) {
+        (OrbitalKind::Moon, true) => "{moon_near}",
+        (OrbitalKind::Moon, false) => "{moon_far}",
+        (_, true) => "{near}",
+        (_, false) => "{far}",
+    };
+    let distance = expand(&mut rng, distance, &[], 0);
+
+    let vars = [
+        ("name", body.name.clone()),
+        ("kind", kind.to_string()),
+        ("called", called),
+        ("called_again", called_again),
+        ("A_tone", capitalise(&a_tone)),
+        ("a_tone", a_tone),
+        ("detail", detail),
+        ("distance", distance),
+    ];
+    expand(&mut rng, "{body}", &vars, 0)
+}
 
-    None
+/// A line of flavour for `system`, from its stars and what orbits them.
+pub fn describe_system(system: &StarSystem) -> String {
+    let mut rng = lore_rng(&format!("system#{}", system.id));
+    let names: Vec<&str> = system.stars.iter().map(|s| s.name.as_str()).collect();
+    let stars = match names.as_slice() {
+        [] => "No star".to_string(),
+        [one] => format!("The lone star {}", one),
+        [first, second] => format!("The twin stars {} and {}", first, second),
+        [rest @ .., last] => format!("The stars {} and {}", rest.join(", "), last),
+    };
+    let worlds = system
+        .orbitals
+        .iter()
+        .filter(|b| matches!(b.kind, OrbitalKind::Planetoid))
+        .count();
+    let belts = system.orbitals.len() - worlds;
+    let worlds = match worlds {
+        1 => "a single world".to_string(),
+        n => format!("{} worlds", number_word(n)),
+    };
+    let belts = match belts {
+        0 => String::new(),
+        1 => " and a belt of rock".to_string(),
+        n => format!(" and {} belts of rock", number_word(n)),
+    };
+    let mut chars = stars.chars();
+    let stars_lower = chars
+        .next()
+        .map_or_else(String::new, |c| c.to_lowercase().chain(chars).collect());
+
+    let orbit = match names.len() {
+        1 => "{one_star}",
+        _ => "{many_stars}",
+    };
+    let orbit = expand(&mut rng, orbit, &[], 0);
+
+    let vars = [
+        ("stars", stars),
+        ("orbit", orbit),
+        ("stars_lower", stars_lower),
+        ("worlds", worlds),
+        ("belts", belts),
+    ];
+    expand(&mut rng, "{system}", &vars, 0)
 }
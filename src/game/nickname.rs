@@ -0,0 +1,943 @@
+use crate::game::hazard::HazardKind;
+use crate::game::naming::Shuffle;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gender {
+    Masculine,
+    Feminine,
+    Neuter,
+}
+
+impl Gender {
+    fn idx(self) -> usize {
+        match self {
+            Gender::Masculine => 0,
+            Gender::Feminine => 1,
+            Gender::Neuter => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArticleKind {
+    Definite,
+    Indefinite,
+    Demonstrative,
+}
+
+/// An article in its masculine, feminine and neuter forms.
+#[derive(Debug, PartialEq)]
+pub struct Article {
+    pub kind: ArticleKind,
+    pub forms: [&'static str; 3],
+}
+
+/// An adjective in its masculine, feminine and neuter forms, or its bare
+/// stem in all three for locales with `Endings`.
+#[derive(Debug, PartialEq)]
+pub struct Adjective {
+    pub forms: [&'static str; 3],
+    pub before_noun: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Noun {
+    pub word: &'static str,
+    pub gender: Gender,
+}
+
+/// Extra words a hazard lends to the bodies it sits on.
+#[derive(Debug, PartialEq)]
+pub struct Theme {
+    pub adjectives: &'static [Adjective],
+    pub nouns: &'static [Noun],
+}
+
+/// Adjective endings for locales that decline adjectives by article:
+/// `strong` by gender after no article or an indefinite one, `weak` after
+/// a definite or demonstrative one.
+#[derive(Debug, PartialEq)]
+pub struct Endings {
+    pub strong: [&'static str; 3],
+    pub weak: &'static str,
+}
+
+/// Everything the nickname grammar needs to speak one language.
+#[derive(Debug, PartialEq)]
+pub struct Lexicon {
+    pub articles: &'static [Article],
+    pub adjectives: &'static [Adjective],
+    pub nouns: &'static [Noun],
+    /// Third person singular, to follow the noun.
+    pub verbs: &'static [&'static str],
+    pub radiation: Theme,
+    pub pirates: Theme,
+    pub debris: Theme,
+    pub endings: Option<Endings>,
+    /// Joins two adjectives on the same side of the noun.
+    pub conjunction: Option<&'static str>,
+    /// Article forms swapped before a vowel ("le" to "l'"); forms ending in
+    /// an apostrophe attach to the next word.
+    pub elisions: &'static [(&'static str, &'static str)],
+    pub vowels: &'static str,
+}
+
+impl Lexicon {
+    pub fn theme(&self, hazard: HazardKind) -> &Theme {
+        match hazard {
+            HazardKind::Radiation => &self.radiation,
+            HazardKind::Pirates => &self.pirates,
+            HazardKind::Debris => &self.debris,
+        }
+    }
+
+    fn inflect(
+        &self,
+        adjective: &Adjective,
+        gender: Gender,
+        article: Option<ArticleKind>,
+    ) -> String {
+        let form = adjective.forms[gender.idx()];
+        match &self.endings {
+            Some(endings) => match article {
+                Some(ArticleKind::Definite | ArticleKind::Demonstrative) => {
+                    format!("{}{}", form, endings.weak)
+                }
+                _ => format!("{}{}", form, endings.strong[gender.idx()]),
+            },
+            None => form.to_string(),
+        }
+    }
+
+    fn elide(&self, article: &'static str, next: &str) -> &'static str {
+        if !next.starts_with(|c: char| self.vowels.contains(c)) {
+            return article;
+        }
+        self.elisions
+            .iter()
+            .find(|(full, _)| *full == article)
+            .map_or(article, |(_, short)| short)
+    }
+}
+
+/// Which lexicon nicknames are written in. `Custom` plugs in any other,
+/// but can't be written to a save.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Locale {
+    #[default]
+    English,
+    French,
+    German,
+    #[serde(skip)]
+    Custom(&'static Lexicon),
+}
+
+impl Locale {
+    pub fn lexicon(self) -> &'static Lexicon {
+        match self {
+            Locale::English => &ENGLISH,
+            Locale::French => &FRENCH,
+            Locale::German => &GERMAN,
+            Locale::Custom(lexicon) => lexicon,
+        }
+    }
+}
+
+const fn same(word: &'static str) -> Adjective {
+    Adjective {
+        forms: [word; 3],
+        before_noun: true,
+    }
+}
+
+const fn after(masculine: &'static str, feminine: &'static str) -> Adjective {
+    Adjective {
+        forms: [masculine, feminine, masculine],
+        before_noun: false,
+    }
+}
+
+const fn before(masculine: &'static str, feminine: &'static str) -> Adjective {
+    Adjective {
+        forms: [masculine, feminine, masculine],
+        before_noun: true,
+    }
+}
+
+const fn m(word: &'static str) -> Noun {
+    Noun {
+        word,
+        gender: Gender::Masculine,
+    }
+}
+
+const fn f(word: &'static str) -> Noun {
+    Noun {
+        word,
+        gender: Gender::Feminine,
+    }
+}
+
+const fn n(word: &'static str) -> Noun {
+    Noun {
+        word,
+        gender: Gender::Neuter,
+    }
+}
+
+pub const ENGLISH: Lexicon = Lexicon {
+    articles: &[
+        Article {
+            kind: ArticleKind::Definite,
+            forms: ["The"; 3],
+        },
+        Article {
+            kind: ArticleKind::Indefinite,
+            forms: ["A"; 3],
+        },
+        Article {
+            kind: ArticleKind::Demonstrative,
+            forms: ["This"; 3],
+        },
+        Article {
+            kind: ArticleKind::Demonstrative,
+            forms: ["That"; 3],
+        },
+    ],
+    adjectives: &[
+        same("Silent"),
+        same("Vagrant"),
+        same("Crimson"),
+        same("Iron"),
+        same("Glass"),
+        same("Blue"),
+        same("Fallen"),
+        same("Wandering"),
+        same("Hidden"),
+        same("Verdant"),
+        same("Ashen"),
+        same("Amber"),
+        same("Sable"),
+        same("Gilded"),
+        same("Fractured"),
+        same("Distant"),
+        same("Last"),
+        same("First"),
+        same("Forgotten"),
+        same("Radiant"),
+        same("Cold"),
+        same("Crowned"),
+        same("Broken"),
+        same("Lonely"),
+        same("Burning"),
+        same("Restless"),
+        same("Sleeping"),
+        same("Shattered"),
+        same("Veiled"),
+        same("Northern"),
+        same("Southern"),
+        same("Eastern"),
+        same("Western"),
+        same("Drifting"),
+        same("Silver"),
+    ],
+    nouns: &[
+        n("Garden"),
+        n("Anvil"),
+        n("Wake"),
+        n("Halo"),
+        n("Drifter"),
+        n("Chorus"),
+        n("Spire"),
+        n("Tide"),
+        n("Beacon"),
+        n("Crown"),
+        n("Forge"),
+        n("Harbor"),
+        n("Passage"),
+        n("Pilgrim"),
+        n("Pilgrimage"),
+        n("Whisper"),
+        n("Ember"),
+        n("Comet"),
+        n("Siren"),
+        n("Step"),
+        n("Gate"),
+        n("Veil"),
+        n("Crossing"),
+        n("Hearth"),
+        n("Dawn"),
+        n("Dusk"),
+        n("Eclipse"),
+        n("Bridge"),
+        n("Hollow"),
+        n("Gulf"),
+        n("Ridge"),
+        n("Memory"),
+    ],
+    verbs: &[
+        "Waits", "Sleeps", "Echoes", "Burns", "Drifts", "Remains", "Flickers", "Stands", "Watches",
+        "Fades",
+    ],
+    radiation: Theme {
+        adjectives: &[
+            same("Irradiated"),
+            same("Ionic"),
+            same("Radiant"),
+            same("Searing"),
+        ],
+        nouns: &[n("Flare"), n("Pulse"), n("Glow")],
+    },
+    pirates: Theme {
+        adjectives: &[
+            same("Corsair"),
+            same("Rogue"),
+            same("Scarred"),
+            same("Bloodied"),
+        ],
+        nouns: &[n("Cutlass"), n("Raid"), n("Corsair"), n("Marauder")],
+    },
+    debris: Theme {
+        adjectives: &[
+            same("Shattered"),
+            same("Broken"),
+            same("Sundered"),
+            same("Twisted"),
+        ],
+        nouns: &[n("Wreck"), n("Shard"), n("Graveyard")],
+    },
+    endings: None,
+    conjunction: None,
+    elisions: &[("A", "An")],
+    vowels: "AEIOU",
+};
+
+pub const FRENCH: Lexicon = Lexicon {
+    articles: &[
+        Article {
+            kind: ArticleKind::Definite,
+            forms: ["le", "la", "le"],
+        },
+        Article {
+            kind: ArticleKind::Indefinite,
+            forms: ["un", "une", "un"],
+        },
+        Article {
+            kind: ArticleKind::Demonstrative,
+            forms: ["ce", "cette", "ce"],
+        },
+    ],
+    adjectives: &[
+        after("silencieux", "silencieuse"),
+        after("errant", "errante"),
+        after("pourpre", "pourpre"),
+        after("bleu", "bleue"),
+        after("déchu", "déchue"),
+        after("caché", "cachée"),
+        after("verdoyant", "verdoyante"),
+        after("cendré", "cendrée"),
+        after("doré", "dorée"),
+        after("brisé", "brisée"),
+        after("lointain", "lointaine"),
+        after("oublié", "oubliée"),
+        after("radieux", "radieuse"),
+        after("froid", "froide"),
+        after("couronné", "couronnée"),
+        after("solitaire", "solitaire"),
+        after("ardent", "ardente"),
+        after("endormi", "endormie"),
+        after("voilé", "voilée"),
+        after("argenté", "argentée"),
+        before("dernier", "dernière"),
+        before("premier", "première"),
+        before("grand", "grande"),
+        before("petit", "petite"),
+    ],
+    nouns: &[
+        m("jardin"),
+        f("enclume"),
+        m("sillage"),
+        m("chœur"),
+        f("flèche"),
+        f("marée"),
+        m("phare"),
+        f("couronne"),
+        f("forge"),
+        m("port"),
+        m("passage"),
+        m("pèlerin"),
+        m("murmure"),
+        f("braise"),
+        f("comète"),
+        f("sirène"),
+        f("porte"),
+        m("voile"),
+        f("traversée"),
+        m("foyer"),
+        f("aube"),
+        m("crépuscule"),
+        f("éclipse"),
+        m("pont"),
+        m("golfe"),
+        f("crête"),
+        f("mémoire"),
+    ],
+    verbs: &[
+        "attend", "dort", "résonne", "brûle", "dérive", "demeure", "vacille", "veille",
+    ],
+    radiation: Theme {
+        adjectives: &[
+            after("irradié", "irradiée"),
+            after("ionique", "ionique"),
+            after("brûlant", "brûlante"),
+        ],
+        nouns: &[f("éruption"), f("pulsation"), f("lueur")],
+    },
+    pirates: Theme {
+        adjectives: &[
+            after("corsaire", "corsaire"),
+            after("balafré", "balafrée"),
+            after("sanglant", "sanglante"),
+        ],
+        nouns: &[m("sabre"), m("raid"), m("corsaire"), m("maraudeur")],
+    },
+    debris: Theme {
+        adjectives: &[
+            after("fracassé", "fracassée"),
+            after("tordu", "tordue"),
+            after("éclaté", "éclatée"),
+        ],
+        nouns: &[f("épave"), m("éclat"), m("cimetière")],
+    },
+    endings: None,
+    conjunction: Some("et"),
+    elisions: &[("le", "l'"), ("la", "l'"), ("ce", "cet")],
+    vowels: "aeiouhéèêâîôûœ",
+};
+
+pub const GERMAN: Lexicon = Lexicon {
+    articles: &[
+        Article {
+            kind: ArticleKind::Definite,
+            forms: ["der", "die", "das"],
+        },
+        Article {
+            kind: ArticleKind::Indefinite,
+            forms: ["ein", "eine", "ein"],
+        },
+        Article {
+            kind: ArticleKind::Demonstrative,
+            forms: ["dieser", "diese", "dieses"],
+        },
+    ],
+    adjectives: &[
+        same("still"),
+        same("wandernd"),
+        same("purpurn"),
+        same("eisern"),
+        same("gläsern"),
+        same("blau"),
+        same("gefallen"),
+        same("verborgen"),
+        same("grün"),
+        same("golden"),
+        same("gebrochen"),
+        same("fern"),
+        same("letzt"),
+        same("erst"),
+        same("vergessen"),
+        same("strahlend"),
+        same("kalt"),
+        same("gekrönt"),
+        same("einsam"),
+        same("brennend"),
+        same("ruhelos"),
+        same("schlafend"),
+        same("verschleiert"),
+        same("silbern"),
+        same("treibend"),
+    ],
+    nouns: &[
+        m("Garten"),
+        m("Amboss"),
+        n("Kielwasser"),
+        m("Chor"),
+        m("Turm"),
+        f("Flut"),
+        n("Leuchtfeuer"),
+        f("Krone"),
+        f("Schmiede"),
+        m("Hafen"),
+        f("Passage"),
+        m("Pilger"),
+        n("Flüstern"),
+        f("Glut"),
+        m("Komet"),
+        f("Sirene"),
+        n("Tor"),
+        m("Schleier"),
+        m("Herd"),
+        m("Morgen"),
+        f("Finsternis"),
+        f("Brücke"),
+        f("Senke"),
+        m("Grat"),
+        f("Erinnerung"),
+    ],
+    verbs: &[
+        "wartet", "schläft", "hallt", "brennt", "treibt", "bleibt", "flackert", "wacht",
+    ],
+    radiation: Theme {
+        adjectives: &[same("verstrahlt"), same("gleißend"), same("sengend")],
+        nouns: &[f("Eruption"), m("Puls"), n("Glühen")],
+    },
+    pirates: Theme {
+        adjectives: &[same("verwegen"), same("vernarbt"), same("blutig")],
+        nouns: &[m("Säbel"), m("Überfall"), m("Korsar"), m("Plünderer")],
+    },
+    debris: Theme {
+        adjectives: &[same("zerschmettert"), same("zerbrochen"), same("verdreht")],
+        nouns: &[n("Wrack"), m("Splitter"), m("Friedhof")],
+    },
+    endings: Some(Endings {
+        strong: ["er", "e", "es"],
+        weak: "e",
+    }),
+    conjunction: None,
+    elisions: &[],
+    vowels: "",
+};
+
+fn pick_from<'a, T>(rng: &mut ChaCha8Rng, options: &'a [T]) -> &'a T {
+    &options[rng.gen_range(0..options.len())]
+}
+
+/// Draws from the themes of every hazard on the body a third of the time,
+/// otherwise from the general list.
+fn themed<'a, T>(
+    rng: &mut ChaCha8Rng,
+    lexicon: &'a Lexicon,
+    hazards: &[HazardKind],
+    general: &'a [T],
+    words: impl Fn(&'a Theme) -> &'a [T],
+) -> &'a T {
+    let mut kinds: Vec<HazardKind> = Vec::new();
+    for kind in hazards {
+        if !kinds.contains(kind) {
+            kinds.push(*kind);
+        }
+    }
+    let total: usize = kinds.iter().map(|k| words(lexicon.theme(*k)).len()).sum();
+    if total > 0 && rng.gen::<f64>() < 0.35 {
+        let mut idx = rng.gen_range(0..total);
+        for kind in kinds {
+            let themed = words(lexicon.theme(kind));
+            if idx < themed.len() {
+                return &themed[idx];
+            }
+            idx -= themed.len();
+        }
+    }
+    pick_from(rng, general)
+}
+
+pub fn themed_adjective<'a>(
+    rng: &mut ChaCha8Rng,
+    lexicon: &'a Lexicon,
+    hazards: &[HazardKind],
+) -> &'a Adjective {
+    themed(rng, lexicon, hazards, lexicon.adjectives, |t| t.adjectives)
+}
+
+pub fn themed_noun<'a>(
+    rng: &mut ChaCha8Rng,
+    lexicon: &'a Lexicon,
+    hazards: &[HazardKind],
+) -> &'a Noun {
+    themed(rng, lexicon, hazards, lexicon.nouns, |t| t.nouns)
+}
+
+#[derive(Clone, Copy)]
+enum Token {
+    Article,
+    Adjective,
+    Noun,
+    Verb,
+}
+
+const PATTERNS: &[(&[Token], u32)] = &[
+    (&[Token::Noun], 4),
+    (&[Token::Adjective, Token::Noun], 4),
+    (&[Token::Article, Token::Noun], 3),
+    (&[Token::Article, Token::Adjective, Token::Noun], 2),
+    (&[Token::Adjective, Token::Adjective, Token::Noun], 1),
+    (&[Token::Article, Token::Noun, Token::Verb], 1),
+    (&[Token::Adjective, Token::Noun, Token::Verb], 1),
+    (
+        &[Token::Article, Token::Adjective, Token::Noun, Token::Verb],
+        1,
+    ),
+];
+
+/// The words of one nickname before they are put in order.
+struct Phrase<'a> {
+    article: Option<&'a Article>,
+    adjectives: Vec<&'a Adjective>,
+    noun: Option<&'a Noun>,
+    verb: Option<&'static str>,
+}
+
+impl Lexicon {
+    /// Puts `phrase` in order: article, adjectives that lead, noun,
+    /// adjectives that follow, verb. Adjectives agree with the noun and
+    /// article, and the article elides into whatever comes next.
+    fn render(&self, phrase: &Phrase) -> Option<String> {
+        let noun = phrase.noun?;
+        let kind = phrase.article.map(|a| a.kind);
+        let side = |leading: bool| -> Vec<String> {
+            phrase
+                .adjectives
+                .iter()
+                .filter(|a| a.before_noun == leading)
+                .map(|a| self.inflect(a, noun.gender, kind))
+                .collect()
+        };
+        let join = |words: Vec<String>| match self.conjunction {
+            Some(and) if words.len() > 1 => words.join(&format!(" {} ", and)),
+            _ => words.join(" "),
+        };
+
+        let mut words: Vec<String> = Vec::new();
+        let leading = side(true);
+        if !leading.is_empty() {
+            words.push(join(leading));
+        }
+        words.push(noun.word.to_string());
+        let trailing = side(false);
+        if !trailing.is_empty() {
+            words.push(join(trailing));
+        }
+        if let Some(verb) = phrase.verb {
+            words.push(verb.to_string());
+        }
+
+        let mut text = words.join(" ");
+        if let Some(article) = phrase.article {
+            let form = self.elide(article.forms[noun.gender.idx()], &text);
+            text = if form.ends_with('\'') {
+                format!("{}{}", form, text)
+            } else {
+                format!("{} {}", form, text)
+            };
+        }
+        let mut chars = text.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+    }
+}
+
+/// The words one body's nicknames are built from: the general lists plus
+/// the themes of every hazard on it, each word once.
+#[derive(Debug)]
+struct Pools<'a> {
+    adjectives: Vec<&'a Adjective>,
+    nouns: Vec<&'a Noun>,
+}
+
+impl<'a> Pools<'a> {
+    fn new(lexicon: &'a Lexicon, hazards: &[HazardKind]) -> Self {
+        let mut adjectives: Vec<&Adjective> = lexicon.adjectives.iter().collect();
+        let mut nouns: Vec<&Noun> = lexicon.nouns.iter().collect();
+        for kind in hazards {
+            let theme = lexicon.theme(*kind);
+            for adjective in theme.adjectives {
+                if !adjectives.contains(&adjective) {
+                    adjectives.push(adjective);
+                }
+            }
+            for noun in theme.nouns {
+                if !nouns.contains(&noun) {
+                    nouns.push(noun);
+                }
+            }
+        }
+        Self { adjectives, nouns }
+    }
+
+    fn choices(&self, lexicon: &Lexicon, token: Token) -> u64 {
+        let n = match token {
+            Token::Article => lexicon.articles.len(),
+            Token::Adjective => self.adjectives.len(),
+            Token::Noun => self.nouns.len(),
+            Token::Verb => lexicon.verbs.len(),
+        };
+        n as u64
+    }
+
+    /// How many phrases `tokens` can make, counting repeats.
+    fn size(&self, lexicon: &Lexicon, tokens: &[Token]) -> u64 {
+        tokens
+            .iter()
+            .map(|t| self.choices(lexicon, *t))
+            .fold(1, u64::saturating_mul)
+    }
+
+    /// The phrase numbered `index` among those `tokens` can make: one digit
+    /// per token, the last varying fastest.
+    fn phrase_at(&self, lexicon: &'a Lexicon, tokens: &[Token], mut index: u64) -> Phrase<'a> {
+        let mut digits = vec![0; tokens.len()];
+        for (digit, token) in digits.iter_mut().zip(tokens).rev() {
+            let n = self.choices(lexicon, *token);
+            *digit = (index % n) as usize;
+            index /= n;
+        }
+        let mut phrase = Phrase {
+            article: None,
+            adjectives: Vec::new(),
+            noun: None,
+            verb: None,
+        };
+        for (token, digit) in tokens.iter().zip(digits) {
+            match token {
+                Token::Article => phrase.article = Some(&lexicon.articles[digit]),
+                Token::Adjective => {
+                    let adjective = self.adjectives[digit];
+                    if !phrase.adjectives.contains(&adjective) {
+                        phrase.adjectives.push(adjective);
+                    }
+                }
+                Token::Noun => phrase.noun = Some(self.nouns[digit]),
+                Token::Verb => phrase.verb = Some(lexicon.verbs[digit]),
+            }
+        }
+        phrase
+    }
+}
+
+/// The nicknames one lexicon can make for bodies with the given hazards.
+/// Each pick rolls a pattern by weight and walks a shuffle of its phrase
+/// numbers keyed from the caller's stream, so a body's nickname comes from
+/// its own dice alone and only steps along when an earlier body took it.
+#[derive(Debug)]
+pub struct NicknameSpace {
+    lexicon: &'static Lexicon,
+    pools: Pools<'static>,
+    /// Patterns already walked to the end against the `used` set picks
+    /// are made with; a cache, as walking them again finds nothing.
+    full: Vec<bool>,
+}
+
+impl NicknameSpace {
+    pub fn new(lexicon: &'static Lexicon, hazards: &[HazardKind]) -> Self {
+        let pools = Pools::new(lexicon, hazards);
+        let full = PATTERNS
+            .iter()
+            .map(|(tokens, _)| pools.size(lexicon, tokens) == 0)
+            .collect();
+        Self {
+            lexicon,
+            pools,
+            full,
+        }
+    }
+
+    /// A nickname not in `used`, which it is then added to, or `None` once
+    /// every phrase is taken. Callers pass the same `used` set every time.
+    pub fn pick(&mut self, rng: &mut ChaCha8Rng, used: &mut HashSet<String>) -> Option<String> {
+        let mut open: Vec<usize> = (0..PATTERNS.len()).collect();
+        while !open.is_empty() {
+            let total_weight: u32 = open.iter().map(|&i| PATTERNS[i].1).sum();
+            let mut roll = rng.gen_range(0..total_weight);
+            let mut at = 0;
+            while roll >= PATTERNS[open[at]].1 {
+                roll -= PATTERNS[open[at]].1;
+                at += 1;
+            }
+            let pattern = open.remove(at);
+            let key: u64 = rng.gen();
+            if self.full[pattern] {
+                continue;
+            }
+
+            let tokens = PATTERNS[pattern].0;
+            let shuffle = Shuffle::new(self.pools.size(self.lexicon, tokens), key);
+            for step in 0..shuffle.len() {
+                let phrase = self.pools.phrase_at(self.lexicon, tokens, shuffle.at(step));
+                if let Some(nickname) = self.lexicon.render(&phrase) {
+                    if used.insert(nickname.clone()) {
+                        return Some(nickname);
+                    }
+                }
+            }
+            self.full[pattern] = true;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn phrase<'a>(
+        lexicon: &'a Lexicon,
+        article: Option<usize>,
+        adjectives: &[usize],
+        noun: &'a Noun,
+    ) -> Option<String> {
+        lexicon.render(&Phrase {
+            article: article.map(|i| &lexicon.articles[i]),
+            adjectives: adjectives.iter().map(|i| &lexicon.adjectives[*i]).collect(),
+            noun: Some(noun),
+            verb: None,
+        })
+    }
+
+    #[test]
+    fn adjectives_agree_and_articles_elide() {
+        let forge = &FRENCH.nouns[8];
+        let port = &FRENCH.nouns[9];
+        let aube = &FRENCH.nouns[20];
+        assert_eq!(
+            phrase(&FRENCH, Some(0), &[0], forge).unwrap(),
+            "La forge silencieuse"
+        );
+        assert_eq!(
+            phrase(&FRENCH, Some(0), &[0], port).unwrap(),
+            "Le port silencieux"
+        );
+        assert_eq!(
+            phrase(&FRENCH, Some(0), &[22, 8], aube).unwrap(),
+            "La grande aube dorée"
+        );
+        assert_eq!(phrase(&FRENCH, Some(0), &[], aube).unwrap(), "L'aube");
+        assert_eq!(
+            phrase(&FRENCH, Some(2), &[0, 1], port).unwrap(),
+            "Ce port silencieux et errant"
+        );
+
+        let garten = &GERMAN.nouns[0];
+        let krone = &GERMAN.nouns[7];
+        let tor = &GERMAN.nouns[16];
+        assert_eq!(
+            phrase(&GERMAN, Some(0), &[0], garten).unwrap(),
+            "Der stille Garten"
+        );
+        assert_eq!(
+            phrase(&GERMAN, Some(1), &[0], garten).unwrap(),
+            "Ein stiller Garten"
+        );
+        assert_eq!(phrase(&GERMAN, None, &[0], tor).unwrap(), "Stilles Tor");
+        assert_eq!(
+            phrase(&GERMAN, Some(2), &[9], krone).unwrap(),
+            "Diese goldene Krone"
+        );
+
+        let ember = &ENGLISH.nouns[16];
+        assert_eq!(phrase(&ENGLISH, Some(1), &[], ember).unwrap(), "An Ember");
+        assert_eq!(
+            phrase(&ENGLISH, Some(1), &[0], ember).unwrap(),
+            "A Silent Ember"
+        );
+    }
+
+    #[test]
+    fn every_hazard_lends_its_theme() {
+        let hazards = [HazardKind::Radiation, HazardKind::Debris];
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let nouns: HashSet<&str> = (0..400)
+            .map(|_| themed_noun(&mut rng, &ENGLISH, &hazards).word)
+            .collect();
+        assert!(nouns.contains("Flare"));
+        assert!(nouns.contains("Wreck"));
+        assert!(!nouns.contains("Cutlass"));
+
+        for locale in [Locale::English, Locale::French, Locale::German] {
+            let mut used = HashSet::new();
+            let mut nicknames = NicknameSpace::new(locale.lexicon(), &hazards);
+            for _ in 0..50 {
+                let name = nicknames.pick(&mut rng, &mut used).expect("plenty of room");
+                assert!(
+                    name.chars().next().is_some_and(char::is_uppercase),
+                    "{name}"
+                );
+            }
+        }
+
+        let mut used = HashSet::new();
+        let mut themed = NicknameSpace::new(&ENGLISH, &[HazardKind::Pirates]);
+        let words: HashSet<String> = (0..2000)
+            .filter_map(|_| themed.pick(&mut rng, &mut used))
+            .flat_map(|n| n.split(' ').map(str::to_string).collect::<Vec<_>>())
+            .collect();
+        assert!(words.contains("Cutlass"));
+    }
+
+    #[test]
+    fn nickname_picks_scale_to_large_universes() {
+        let mut used = HashSet::new();
+        let mut nicknames = NicknameSpace::new(&ENGLISH, &[]);
+        for body in 0..50_000 {
+            let mut rng = ChaCha8Rng::seed_from_u64(body);
+            nicknames
+                .pick(&mut rng, &mut used)
+                .expect("room for 50k nicknames");
+        }
+        assert_eq!(used.len(), 50_000);
+
+        // A body's pick depends only on its own dice while its name is free.
+        let mut first = HashSet::new();
+        let mut second = HashSet::new();
+        second.insert("Someone Else".to_string());
+        let a = nicknames.pick(&mut ChaCha8Rng::seed_from_u64(9), &mut first);
+        let b =
+            NicknameSpace::new(&ENGLISH, &[]).pick(&mut ChaCha8Rng::seed_from_u64(9), &mut second);
+        assert_eq!(a, b);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+
+        const TINY: Lexicon = Lexicon {
+            articles: &[Article {
+                kind: ArticleKind::Definite,
+                forms: ["the"; 3],
+            }],
+            adjectives: &[Adjective {
+                forms: ["Bright"; 3],
+                before_noun: true,
+            }],
+            nouns: &[Noun {
+                word: "Star",
+                gender: Gender::Neuter,
+            }],
+            verbs: &["Waits"],
+            radiation: Theme {
+                adjectives: &[],
+                nouns: &[],
+            },
+            pirates: Theme {
+                adjectives: &[],
+                nouns: &[],
+            },
+            debris: Theme {
+                adjectives: &[],
+                nouns: &[],
+            },
+            endings: None,
+            conjunction: None,
+            elisions: &[],
+            vowels: "aeiou",
+        };
+        let mut used = HashSet::new();
+        let mut tiny = NicknameSpace::new(&TINY, &[]);
+        while tiny.pick(&mut rng, &mut used).is_some() {}
+        assert_eq!(used.len(), 7);
+        assert_eq!(tiny.pick(&mut rng, &mut used), None);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::game::colony::StructureKind;
+use crate::game::economy::{structure_cost, COLONY_COST, MAX_MINES_PER_COLONY};
+use crate::game::faction::Stance;
+use crate::game::fleet::{Fleet, FleetId};
+use crate::game::player::PlayerId;
+use crate::game::report::Phase;
+use crate::game::tech::{tech_info, Tech};
+use crate::game::Game;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Order {
+    /// Set off for an adjacent system, arriving after `LINK_LENGTH / speed`
+    /// turns, rounded up.
+    MoveFleet {
+        fleet: FleetId,
+        to: u32,
+    },
+    LaunchProbe {
+        fleet: FleetId,
+        body: u32,
+    },
+    Build {
+        body: u32,
+        structure: StructureKind,
+    },
+    Colonise {
+        fleet: FleetId,
+        body: u32,
+    },
+    /// Follow a chain of linked systems; each hop takes `LINK_LENGTH / speed`
+    /// turns, rounded up.
+    SetRoute {
+        fleet: FleetId,
+        route: Vec<u32>,
+    },
+    SetStance {
+        toward: PlayerId,
+        stance: Stance,
+    },
+    Research {
+        tech: Tech,
+    },
+}
+
+impl Order {
+    pub fn phase(&self) -> Phase {
+        match self {
+            Order::SetStance { .. } => Phase::Diplomacy,
+            Order::MoveFleet { .. } | Order::SetRoute { .. } => Phase::Movement,
+            Order::LaunchProbe { .. } => Phase::Probes,
+            Order::Colonise { .. } => Phase::Colonisation,
+            Order::Build { .. } => Phase::Construction,
+            Order::Research { .. } => Phase::Research,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PendingOrder {
+    pub player: PlayerId,
+    pub order: Order,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum OrderRejection {
+    Malformed { message: String },
+    UnknownPlayer { player: PlayerId },
+    SelfTarget,
+    UnknownFleet { fleet: FleetId },
+    NotYourFleet { fleet: FleetId },
+    FleetInTransit { fleet: FleetId },
+    UnknownSystem { system: u32 },
+    NotLinked { from: u32, to: u32 },
+    EmptyRoute,
+    UnknownBody { body: u32 },
+    BodyOutOfReach { fleet: FleetId, body: u32 },
+    NoProbesLeft { fleet: FleetId },
+    NotSurveyed { body: u32 },
+    AlreadyColonised { body: u32 },
+    NotYourColony { body: u32 },
+    AlreadyBuilt { body: u32, structure: StructureKind },
+    ColonyFull { body: u32 },
+    InsufficientCredits { cost: f64, available: f64 },
+    AlreadyResearched { tech: Tech },
+    MissingPrerequisite { tech: Tech, requires: Tech },
+    GameOver,
+}
+
+fn owned_fleet(game: &Game, player: PlayerId, fleet: FleetId) -> Result<&Fleet, OrderRejection> {
+    let found = game
+        .fleet(fleet)
+        .ok_or(OrderRejection::UnknownFleet { fleet })?;
+    if found.owner != player {
+        return Err(OrderRejection::NotYourFleet { fleet });
+    }
+    Ok(found)
+}
+
+fn check_route(game: &Game, start: u32, route: &[u32]) -> Result<(), OrderRejection> {
+    if route.is_empty() {
+        return Err(OrderRejection::EmptyRoute);
+    }
+    let mut from = start;
+    for &to in route {
+        if game.universe().system(to).is_none() {
+            return Err(OrderRejection::UnknownSystem { system: to });
+        }
+        let linked = game
+            .universe()
+            .system(from)
+            .is_some_and(|sys| sys.links.contains(&to));
+        if !linked {
+            return Err(OrderRejection::NotLinked { from, to });
+        }
+        from = to;
+    }
+    Ok(())
+}
+
+fn afford(credits: f64, cost: f64) -> Result<(), OrderRejection> {
+    if credits < cost {
+        return Err(OrderRejection::InsufficientCredits {
+            cost,
+            available: credits,
+        });
+    }
+    Ok(())
+}
+
+fn fleet_at_body(game: &Game, fleet: &Fleet, body: u32) -> Result<(), OrderRejection> {
+    if !fleet.is_docked() {
+        return Err(OrderRejection::FleetInTransit { fleet: fleet.id });
+    }
+    let (system, _) = game
+        .universe()
+        .locate_body(body)
+        .ok_or(OrderRejection::UnknownBody { body })?;
+    if system.id != fleet.system {
+        return Err(OrderRejection::BodyOutOfReach {
+            fleet: fleet.id,
+            body,
+        });
+    }
+    Ok(())
+}
+
+/// Checks an order against the current game state. The same checks run again
+/// when the order resolves, since earlier orders in the turn may invalidate it.
+pub fn validate(game: &Game, player: PlayerId, order: &Order) -> Result<(), OrderRejection> {
+    if game.outcome().is_some() {
+        return Err(OrderRejection::GameOver);
+    }
+    let viewer = game
+        .player(player)
+        .ok_or(OrderRejection::UnknownPlayer { player })?;
+
+    match order {
+        Order::SetStance { toward, .. } => {
+            if *toward == player {
+                return Err(OrderRejection::SelfTarget);
+            }
+            game.player(*toward)
+                .map(|_| ())
+                .ok_or(OrderRejection::UnknownPlayer { player: *toward })
+        }
+        Order::MoveFleet { fleet, to } => {
+            let fleet = owned_fleet(game, player, *fleet)?;
+            check_route(game, fleet.next_stop(), &[*to])
+        }
+        Order::SetRoute { fleet, route } => {
+            let fleet = owned_fleet(game, player, *fleet)?;
+            check_route(game, fleet.next_stop(), route)
+        }
+        Order::LaunchProbe { fleet, body } => {
+            let fleet = owned_fleet(game, player, *fleet)?;
+            fleet_at_body(game, fleet, *body)?;
+            if fleet.probes == 0 {
+                return Err(OrderRejection::NoProbesLeft { fleet: fleet.id });
+            }
+            Ok(())
+        }
+        Order::Colonise { fleet, body } => {
+            let fleet = owned_fleet(game, player, *fleet)?;
+            fleet_at_body(game, fleet, *body)?;
+            if !viewer.knowledge.is_surveyed(*body) {
+                return Err(OrderRejection::NotSurveyed { body: *body });
+            }
+            if game.colony(*body).is_some() {
+                return Err(OrderRejection::AlreadyColonised { body: *body });
+            }
+            afford(viewer.stockpile.credits, COLONY_COST)
+        }
+        Order::Build { body, structure } => {
+            let colony = match game.colony(*body) {
+                Some(colony) if colony.owner == player => colony,
+                _ => return Err(OrderRejection::NotYourColony { body: *body }),
+            };
+            let existing = colony.structures.iter().filter(|s| *s == structure).count();
+            match structure {
+                StructureKind::Outpost if existing > 0 => {
+                    return Err(OrderRejection::AlreadyBuilt {
+                        body: *body,
+                        structure: *structure,
+                    })
+                }
+                StructureKind::Mine if existing >= MAX_MINES_PER_COLONY => {
+                    return Err(OrderRejection::ColonyFull { body: *body })
+                }
+                _ => {}
+            }
+            afford(viewer.stockpile.credits, structure_cost(*structure))
+        }
+        Order::Research { tech } => {
+            if viewer.research.has(*tech) {
+                return Err(OrderRejection::AlreadyResearched { tech: *tech });
+            }
+            if let Some(requires) = viewer.research.missing_prerequisite(*tech) {
+                return Err(OrderRejection::MissingPrerequisite {
+                    tech: *tech,
+                    requires,
+                });
+            }
+            afford(viewer.stockpile.credits, tech_info(*tech).cost)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SubmittedOrder {
+    player: PlayerId,
+    order: Order,
+}
+
+#[derive(Serialize)]
+struct OrderResponse {
+    accepted: bool,
+    rejection: Option<OrderRejection>,
+}
+
+/// Parses `{"player": 0, "order": {"type": "move_fleet", ...}}`, queues it if
+/// valid and answers with `{"accepted": bool, "rejection": {...} | null}`.
+pub fn submit_json(game: &mut Game, json: &str) -> String {
+    let result = serde_json::from_str::<SubmittedOrder>(json)
+        .map_err(|e| OrderRejection::Malformed {
+            message: e.to_string(),
+        })
+        .and_then(|submitted| game.submit(submitted.player, submitted.order));
+    let response = OrderResponse {
+        accepted: result.is_ok(),
+        rejection: result.err(),
+    };
+    serde_json::to_string(&response).unwrap_or_else(|_| "{}".to_string())
+}
@@ -0,0 +1,36 @@
+use crate::game::economy::Stockpile;
+use crate::game::knowledge::Knowledge;
+use crate::game::tech::Research;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+pub type PlayerId = u32;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Player {
+    pub id: PlayerId,
+    pub name: String,
+    pub home_system: u32,
+    pub knowledge: Knowledge,
+    pub stockpile: Stockpile,
+    pub research: Research,
+    /// This faction's own stream for probe, hazard and intel rolls.
+    #[serde(with = "crate::game::save::rng_state")]
+    pub rng: ChaCha8Rng,
+}
+
+impl Player {
+    pub fn new(id: PlayerId, name: String, home_system: u32, rng: ChaCha8Rng) -> Self {
+        let mut knowledge = Knowledge::new();
+        knowledge.reveal_system(home_system);
+        Self {
+            id,
+            name,
+            home_system,
+            knowledge,
+            stockpile: Stockpile::default(),
+            research: Research::default(),
+            rng,
+        }
+    }
+}
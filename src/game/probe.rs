@@ -0,0 +1,193 @@
+use crate::game::hazard::{
+    accumulate_risk, accumulate_risk_with, Hazard, HazardKind, TechModifiers,
+};
+use crate::game::system::{OrbitalBody, BASE_PROBE_FAILURE};
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SurveyReport {
+    pub body_id: u32,
+    pub hazards: Vec<HazardKind>,
+    pub probe_failure: f64,
+    pub yield_multiplier: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProbeOutcome {
+    Lost {
+        body_id: u32,
+    },
+    Surveyed {
+        hull_damage: f64,
+        report: SurveyReport,
+    },
+}
+
+impl ProbeOutcome {
+    pub fn is_lost(&self) -> bool {
+        matches!(self, ProbeOutcome::Lost { .. })
+    }
+}
+
+pub(crate) fn roll_hull_damage(rng: &mut ChaCha8Rng, hazards: &[Hazard]) -> f64 {
+    // Each hazard scratches the hull by up to its profile's hull_damage.
+    let mut damage = 0.0;
+    for h in hazards {
+        damage += h.profile.hull_damage * rng.gen::<f64>();
+    }
+    damage.min(1.0)
+}
+
+pub fn launch_probe(rng: &mut ChaCha8Rng, body: &OrbitalBody) -> ProbeOutcome {
+    launch_probe_with(rng, body, &TechModifiers::default())
+}
+
+/// A probe launched by a player whose technology adjusts the hazards.
+pub fn launch_probe_with(
+    rng: &mut ChaCha8Rng,
+    body: &OrbitalBody,
+    modifiers: &TechModifiers,
+) -> ProbeOutcome {
+    let hazards = modifiers.apply(&body.hazards);
+    let risk = accumulate_risk(&hazards);
+    let probe_failure = risk.failure_prob(BASE_PROBE_FAILURE);
+    if rng.gen::<f64>() < probe_failure {
+        return ProbeOutcome::Lost { body_id: body.id };
+    }
+
+    let hull_damage = roll_hull_damage(rng, &hazards);
+    ProbeOutcome::Surveyed {
+        hull_damage,
+        report: SurveyReport {
+            body_id: body.id,
+            hazards: body.hazards.iter().map(|h| h.kind).collect(),
+            probe_failure,
+            yield_multiplier: risk.multiplier,
+        },
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TrialSummary {
+    pub trials: u32,
+    pub failures: u32,
+    pub mean_hull_damage: f64,
+    pub analytic_failure: f64,
+}
+
+impl TrialSummary {
+    pub fn empirical_failure(&self) -> f64 {
+        if self.trials == 0 {
+            return 0.0;
+        }
+        self.failures as f64 / self.trials as f64
+    }
+}
+
+/// Launches `trials` probes at the same body and tallies the results, so the
+/// rolled failure rate can be checked against `RiskChannels::failure_prob`.
+pub fn run_trials(rng: &mut ChaCha8Rng, body: &OrbitalBody, trials: u32) -> TrialSummary {
+    run_trials_with(rng, body, trials, &TechModifiers::default())
+}
+
+pub fn run_trials_with(
+    rng: &mut ChaCha8Rng,
+    body: &OrbitalBody,
+    trials: u32,
+    modifiers: &TechModifiers,
+) -> TrialSummary {
+    let mut failures = 0;
+    let mut total_damage = 0.0;
+    for _ in 0..trials {
+        match launch_probe_with(rng, body, modifiers) {
+            ProbeOutcome::Lost { .. } => failures += 1,
+            ProbeOutcome::Surveyed { hull_damage, .. } => total_damage += hull_damage,
+        }
+    }
+
+    let survivors = trials - failures;
+    TrialSummary {
+        trials,
+        failures,
+        mean_hull_damage: if survivors > 0 {
+            total_damage / survivors as f64
+        } else {
+            0.0
+        },
+        analytic_failure: accumulate_risk_with(&body.hazards, modifiers)
+            .failure_prob(BASE_PROBE_FAILURE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::hazard::hazard_profile;
+    use crate::game::system::OrbitalKind;
+    use rand::SeedableRng;
+
+    fn body_with(kinds: &[HazardKind]) -> OrbitalBody {
+        OrbitalBody {
+            id: 7,
+            name: "Test b".to_string(),
+            nickname: None,
+            distance: 100,
+            yield_rate: 3,
+            hazards: kinds
+                .iter()
+                .map(|&kind| Hazard {
+                    kind,
+                    profile: hazard_profile(kind),
+                })
+                .collect(),
+            kind: OrbitalKind::Planetoid,
+            moons: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn empirical_failure_matches_analytic() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2024);
+        let cases: &[&[HazardKind]] = &[
+            &[],
+            &[HazardKind::Radiation],
+            &[HazardKind::Pirates, HazardKind::Debris],
+            &[HazardKind::Radiation, HazardKind::Debris],
+        ];
+
+        for kinds in cases {
+            let summary = run_trials(&mut rng, &body_with(kinds), 20_000);
+            let diff = (summary.empirical_failure() - summary.analytic_failure).abs();
+            assert!(
+                diff < 0.015,
+                "{:?}: empirical {} vs analytic {}",
+                kinds,
+                summary.empirical_failure(),
+                summary.analytic_failure
+            );
+        }
+    }
+
+    #[test]
+    fn probes_are_deterministic_and_respect_hull_damage() {
+        let body = body_with(&[HazardKind::Pirates]);
+        let mut r1 = ChaCha8Rng::seed_from_u64(5);
+        let mut r2 = ChaCha8Rng::seed_from_u64(5);
+        for _ in 0..50 {
+            let outcome = launch_probe(&mut r1, &body);
+            assert_eq!(outcome, launch_probe(&mut r2, &body));
+            if let ProbeOutcome::Surveyed {
+                hull_damage,
+                report,
+            } = outcome
+            {
+                assert!((0.0..=0.5).contains(&hull_damage));
+                assert_eq!(report.hazards, vec![HazardKind::Pirates]);
+            }
+        }
+
+        let calm = run_trials(&mut r1, &body_with(&[]), 200);
+        assert_eq!(calm.mean_hull_damage, 0.0);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::game::naming::NamingConfig;
+use crate::game::order::PendingOrder;
+use crate::game::system::Universe;
+use crate::game::victory::VictoryConfig;
+use crate::game::Game;
+use rand_chacha::ChaCha8Rng;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// FNV-1a over the serialised game state. Stable across platforms, which is
+/// all a replay check needs; it is not meant to resist tampering.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checksum(pub u64);
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl Checksum {
+    pub fn of(bytes: &[u8]) -> Self {
+        Self(bytes.iter().fold(FNV_OFFSET, |hash, &b| {
+            (hash ^ b as u64).wrapping_mul(FNV_PRIME)
+        }))
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+// Kept as hex so the full 64 bits survive JavaScript numbers.
+impl Serialize for Checksum {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Checksum {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        u64::from_str_radix(&hex, 16)
+            .map(Checksum)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Everything resolved in one tick, and the state checksum it produced.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LoggedTurn {
+    pub turn: u32,
+    pub orders: Vec<PendingOrder>,
+    pub checksum: Checksum,
+}
+
+/// The seed and setup a game started from, plus every turn since. Enough to
+/// rebuild the game exactly as of any turn.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OrderLog {
+    pub seed: u64,
+    pub factions: usize,
+    /// Logs from before victory conditions were configurable played to the
+    /// defaults.
+    #[serde(default)]
+    pub victory: VictoryConfig,
+    /// The map for games started on a hand-authored universe; generated
+    /// maps are rebuilt from the seed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub universe: Option<Universe>,
+    /// How stars and factions were named. Custom nickname lexicons can't be
+    /// written down, so games using one keep their `universe` here instead.
+    #[serde(default, skip_serializing_if = "NamingConfig::is_default")]
+    pub naming: NamingConfig,
+    /// Faction names and dice as they stood before the first turn, for
+    /// games started under a naming scheme that has since changed. Empty
+    /// means they follow from the seed and `naming`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub starts: Vec<FactionStart>,
+    pub turns: Vec<LoggedTurn>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FactionStart {
+    pub name: String,
+    #[serde(with = "crate::game::save::rng_state")]
+    pub rng: ChaCha8Rng,
+}
+
+impl OrderLog {
+    pub fn new(seed: u64, factions: usize, victory: VictoryConfig) -> Self {
+        Self {
+            seed,
+            factions,
+            victory,
+            universe: None,
+            naming: NamingConfig::default(),
+            starts: Vec::new(),
+            turns: Vec::new(),
+        }
+    }
+
+    pub fn last_turn(&self) -> u32 {
+        self.turns.last().map_or(0, |t| t.turn)
+    }
+}
+
+/// Games keep a full copy of their state every this many turns, so a rewind
+/// only replays the turns since the nearest one.
+pub const SNAPSHOT_INTERVAL: u32 = 10;
+
+/// A copy of the game as it stood after `turn`. Snapshots are a cache over
+/// the order log and are not saved; a loaded game rewinds by replaying.
+#[derive(Clone)]
+pub struct Snapshot {
+    pub turn: u32,
+    pub checksum: Checksum,
+    pub(crate) state: Box<Game>,
+}
+
+#[derive(Serialize)]
+pub struct SnapshotView {
+    turn: u32,
+    checksum: Checksum,
+}
+
+impl From<&Snapshot> for SnapshotView {
+    fn from(snapshot: &Snapshot) -> Self {
+        Self {
+            turn: snapshot.turn,
+            checksum: snapshot.checksum,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplayError {
+    Malformed(String),
+    NoLog,
+    TurnOutOfRange {
+        turn: u32,
+        last: u32,
+    },
+    Diverged {
+        turn: u32,
+        expected: Checksum,
+        found: Checksum,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Malformed(msg) => write!(f, "malformed order log: {}", msg),
+            ReplayError::NoLog => write!(f, "this game has no order log"),
+            ReplayError::TurnOutOfRange { turn, last } => {
+                write!(f, "turn {} is not in the log (last turn {})", turn, last)
+            }
+            ReplayError::Diverged {
+                turn,
+                expected,
+                found,
+            } => write!(
+                f,
+                "replay diverged on turn {}: expected {}, got {}",
+                turn, expected, found
+            ),
+        }
+    }
+}
+
+/// Rebuilds the game as it stood after `turn`, checking every replayed turn
+/// against the checksum recorded when it was first played.
+pub fn replay(log: &OrderLog, turn: u32) -> Result<Game, ReplayError> {
+    if turn > log.last_turn() {
+        return Err(ReplayError::TurnOutOfRange {
+            turn,
+            last: log.last_turn(),
+        });
+    }
+
+    let mut game = Game::from_log(log);
+    replay_turns(&mut game, log.turns.iter().take_while(|t| t.turn <= turn))?;
+    Ok(game)
+}
+
+/// Plays `turns` forward from `game`, which must stand just before the first
+/// of them.
+pub(crate) fn replay_turns<'a>(
+    game: &mut Game,
+    turns: impl Iterator<Item = &'a LoggedTurn>,
+) -> Result<(), ReplayError> {
+    for logged in turns {
+        // The orders passed validation against this very state the first
+        // time round, so they are queued as-is.
+        game.pending = logged.orders.clone();
+        game.tick();
+        let found = game.checksum();
+        if found != logged.checksum {
+            return Err(ReplayError::Diverged {
+                turn: logged.turn,
+                expected: logged.checksum,
+                found,
+            });
+        }
+    }
+    Ok(())
+}
+
+pub fn replay_json(log: &str, turn: u32) -> Result<Game, ReplayError> {
+    let log: OrderLog =
+        serde_json::from_str(log).map_err(|e| ReplayError::Malformed(e.to_string()))?;
+    replay(&log, turn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ai::{AiPlayer, ExplorerAi};
+    use crate::game::faction::Stance;
+    use crate::game::order::Order;
+    use crate::game::player::PlayerId;
+    use crate::game::save::{load_game, save_game};
+
+    fn play(seed: u64, turns: u32) -> (Game, Vec<Checksum>) {
+        let mut game = Game::with_factions(seed, 2);
+        let mut checksums = Vec::new();
+        play_on(&mut game, turns, &mut checksums);
+        (game, checksums)
+    }
+
+    fn play_on(game: &mut Game, turns: u32, checksums: &mut Vec<Checksum>) {
+        let mut ais = [ExplorerAi::new(), ExplorerAi::new()];
+        for _ in 0..turns {
+            for (idx, ai) in ais.iter_mut().enumerate() {
+                for order in ai.issue_orders(game, idx as PlayerId) {
+                    let _ = game.submit(idx as PlayerId, order);
+                }
+            }
+            game.tick();
+            checksums.push(game.checksum());
+        }
+    }
+
+    #[test]
+    fn replaying_ai_turns_reproduces_every_state() {
+        let (game, checksums) = play(404, 100);
+        let log = game.order_log().expect("new games keep a log");
+        assert_eq!(log.last_turn(), 100);
+        assert!(log.turns.iter().any(|t| !t.orders.is_empty()));
+
+        let replayed = replay(log, 100).expect("replay matches");
+        assert_eq!(replayed.checksum(), game.checksum());
+        assert_eq!(replayed.order_log(), game.order_log());
+
+        for turn in [0, 1, 37, 99] {
+            let earlier = replay(log, turn).expect("replay matches");
+            assert_eq!(earlier.turn(), turn);
+            if turn > 0 {
+                assert_eq!(earlier.checksum(), checksums[turn as usize - 1]);
+            }
+        }
+
+        let json = serde_json::to_string(log).unwrap();
+        let from_json = replay_json(&json, 100).expect("log survives JSON");
+        assert_eq!(from_json.checksum(), game.checksum());
+    }
+
+    #[test]
+    fn tampered_logs_are_caught() {
+        let (game, _) = play(405, 20);
+        let mut log = game.order_log().unwrap().clone();
+        assert_eq!(
+            replay(&log, 21).err(),
+            Some(ReplayError::TurnOutOfRange { turn: 21, last: 20 })
+        );
+
+        let busy = log
+            .turns
+            .iter()
+            .position(|t| !t.orders.is_empty())
+            .expect("explorers issue orders");
+        log.turns[busy].orders.clear();
+        assert!(matches!(
+            replay(&log, 20),
+            Err(ReplayError::Diverged { turn, .. }) if turn == busy as u32 + 1
+        ));
+        assert!(matches!(
+            replay_json("[]", 1),
+            Err(ReplayError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn rewinding_restores_earlier_turns_and_branches() {
+        let (mut game, checksums) = play(406, 25);
+        let turns: Vec<u32> = game.snapshots().iter().map(|s| s.turn).collect();
+        assert_eq!(turns, vec![0, 10, 20]);
+
+        game.rewind(13)
+            .expect("rewind replays from the turn 10 snapshot");
+        assert_eq!(game.turn(), 13);
+        assert_eq!(game.checksum(), checksums[12]);
+        assert_eq!(game.order_log().unwrap().last_turn(), 13);
+        let turns: Vec<u32> = game.snapshots().iter().map(|s| s.turn).collect();
+        assert_eq!(turns, vec![0, 10]);
+        assert_eq!(
+            game.rewind(14),
+            Err(ReplayError::TurnOutOfRange { turn: 14, last: 13 })
+        );
+
+        // Play a different turn 14, then check the branch replays cleanly.
+        game.submit(
+            0,
+            Order::SetStance {
+                toward: 1,
+                stance: Stance::War,
+            },
+        )
+        .unwrap();
+        game.tick();
+        assert_ne!(game.checksum(), checksums[13]);
+        let branched = replay(game.order_log().unwrap(), 14).expect("branch replays");
+        assert_eq!(branched.checksum(), game.checksum());
+
+        // Saves drop snapshots, so a loaded game rewinds through the log.
+        let mut loaded = load_game(&save_game(&game)).unwrap();
+        assert!(loaded.snapshots().is_empty());
+        loaded.rewind(3).unwrap();
+        assert_eq!(loaded.checksum(), checksums[2]);
+        loaded.rewind(0).unwrap();
+        assert_eq!(loaded.checksum(), Game::with_factions(406, 2).checksum());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::game::colony::StructureKind;
+use crate::game::economy::IncomeReport;
+use crate::game::faction::Stance;
+use crate::game::fleet::{FleetId, HullClass};
+use crate::game::order::OrderRejection;
+use crate::game::player::PlayerId;
+use crate::game::tech::Tech;
+use serde::Serialize;
+
+/// Phases run in this order every tick; orders only act in their own phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Diplomacy,
+    Movement,
+    Probes,
+    Colonisation,
+    Construction,
+    Research,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 6] = [
+        Phase::Diplomacy,
+        Phase::Movement,
+        Phase::Probes,
+        Phase::Colonisation,
+        Phase::Construction,
+        Phase::Research,
+    ];
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ReportEntry {
+    StanceChanged {
+        player: PlayerId,
+        toward: PlayerId,
+        stance: Stance,
+    },
+    /// An alliance offer still waiting for the other side to propose back.
+    AllianceProposed {
+        player: PlayerId,
+        toward: PlayerId,
+    },
+    FleetMoved {
+        fleet: FleetId,
+        from: u32,
+        to: u32,
+    },
+    FleetDamaged {
+        fleet: FleetId,
+        system: u32,
+        damage: f64,
+    },
+    ShipLost {
+        fleet: FleetId,
+        hull: HullClass,
+    },
+    FleetDestroyed {
+        fleet: FleetId,
+    },
+    SystemRevealed {
+        player: PlayerId,
+        system: u32,
+    },
+    ProbeLost {
+        player: PlayerId,
+        body: u32,
+    },
+    BodySurveyed {
+        player: PlayerId,
+        body: u32,
+        hull_damage: f64,
+    },
+    ColonyFounded {
+        player: PlayerId,
+        body: u32,
+    },
+    StructureBuilt {
+        player: PlayerId,
+        body: u32,
+        structure: StructureKind,
+    },
+    TechResearched {
+        player: PlayerId,
+        tech: Tech,
+    },
+    OrderFailed {
+        player: PlayerId,
+        phase: Phase,
+        rejection: OrderRejection,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TurnReport {
+    pub turn: u32,
+    pub entries: Vec<ReportEntry>,
+    pub income: Vec<IncomeReport>,
+}
+
+impl TurnReport {
+    pub fn new(turn: u32) -> Self {
+        Self {
+            turn,
+            entries: Vec::new(),
+            income: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, entry: ReportEntry) {
+        self.entries.push(entry);
+    }
+}
@@ -0,0 +1,386 @@
+use crate::game::faction::faction_rng;
+use crate::game::naming::NameStyle;
+use crate::game::player::PlayerId;
+use crate::game::replay::FactionStart;
+use crate::game::victory::VictoryConfig;
+use crate::game::Game;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// Bump whenever the saved shape of `Game` changes, and add the matching
+/// upgrade to `MIGRATIONS`.
+pub const SAVE_VERSION: u32 = 7;
+
+/// `MIGRATIONS[n]` upgrades the `game` payload of a version `n + 1` save to
+/// version `n + 2`, so any older save can be walked forward step by step.
+const MIGRATIONS: &[fn(&mut Value)] = &[
+    v1_without_order_log,
+    v2_without_events,
+    v3_without_victory,
+    v4_without_research,
+    v5_without_pinned_setup,
+    v6_without_pinned_map,
+];
+
+const _: () = assert!(MIGRATIONS.len() == SAVE_VERSION as usize - 1);
+
+/// Version 1 predates the order log. The seed can't be recovered from the
+/// RNG state, so those games simply carry no log.
+fn v1_without_order_log(game: &mut Value) {
+    if let Some(fields) = game.as_object_mut() {
+        fields.insert("log".to_string(), Value::Null);
+    }
+}
+
+/// Version 2 predates the event log; those games start with an empty one.
+fn v2_without_events(game: &mut Value) {
+    if let Some(fields) = game.as_object_mut() {
+        fields.insert("events".to_string(), serde_json::json!({ "log": [] }));
+    }
+}
+
+/// Version 3 predates victory conditions; those games play to the defaults.
+fn v3_without_victory(game: &mut Value) {
+    if let Some(fields) = game.as_object_mut() {
+        let victory = serde_json::to_value(VictoryConfig::default()).unwrap_or(Value::Null);
+        fields.insert("victory".to_string(), victory);
+        fields.insert("outcome".to_string(), Value::Null);
+    }
+}
+
+/// Version 4 predates research; every faction starts with nothing unlocked.
+fn v4_without_research(game: &mut Value) {
+    let players = game.get_mut("players").and_then(Value::as_array_mut);
+    for player in players.into_iter().flatten() {
+        if let Some(fields) = player.as_object_mut() {
+            fields.insert(
+                "research".to_string(),
+                serde_json::json!({ "unlocked": [] }),
+            );
+        }
+    }
+}
+
+/// Version 5 games were named, and their maps generated, by code that has
+/// since changed, so replaying their logs from the seed alone would build a
+/// different game. The saved map goes into the log as is, along with the
+/// faction names and the dice those names left behind.
+fn v5_without_pinned_setup(game: &mut Value) {
+    let names: Vec<String> = game
+        .get("players")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|p| p.get("name").and_then(Value::as_str).map(str::to_string))
+        .collect();
+    pin_universe(game);
+    let Some(log) = game.get_mut("log").and_then(Value::as_object_mut) else {
+        return;
+    };
+    let Some(seed) = log.get("seed").and_then(Value::as_u64) else {
+        return;
+    };
+    let starts: Option<Vec<FactionStart>> = names
+        .into_iter()
+        .enumerate()
+        .map(|(id, name)| {
+            let rng = v5_faction_rng(seed, id as PlayerId, &name)?;
+            Some(FactionStart { name, rng })
+        })
+        .collect();
+    if let Some(starts) = starts.and_then(|s| serde_json::to_value(s).ok()) {
+        log.insert("starts".to_string(), starts);
+    }
+}
+
+/// Version 6 games handed out nicknames in generation order, so their maps
+/// can't be rebuilt from the seed either; the saved one goes into the log.
+fn v6_without_pinned_map(game: &mut Value) {
+    pin_universe(game);
+}
+
+/// Copies the saved map into the order log, unless it already holds one.
+fn pin_universe(game: &mut Value) {
+    let universe = game.get("universe").cloned().unwrap_or(Value::Null);
+    let Some(log) = game.get_mut("log").and_then(Value::as_object_mut) else {
+        return;
+    };
+    if log.get("universe").is_none_or(Value::is_null) {
+        log.insert("universe".to_string(), universe);
+    }
+}
+
+/// Replays the draws version 5 spent naming a faction: classic star name
+/// candidates until the saved one comes up, then one of the six titles.
+/// `None` for names those draws could not have produced.
+fn v5_faction_rng(seed: u64, faction: PlayerId, name: &str) -> Option<ChaCha8Rng> {
+    let (root, _) = name.split_once(' ')?;
+    let mut rng = faction_rng(seed, faction);
+    for _ in 0..500 {
+        if NameStyle::Classic
+            .candidate(&mut rng)
+            .eq_ignore_ascii_case(root)
+        {
+            rng.gen_range(0..6usize);
+            return Some(rng);
+        }
+    }
+    None
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SaveError {
+    Malformed(String),
+    UnsupportedVersion { found: u64, supported: u32 },
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Malformed(msg) => write!(f, "malformed save: {}", msg),
+            SaveError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "save version {} is not supported (expected 1..={})",
+                found, supported
+            ),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SaveFileRef<'a> {
+    version: u32,
+    game: &'a Game,
+}
+
+pub fn save_game(game: &Game) -> String {
+    let file = SaveFileRef {
+        version: SAVE_VERSION,
+        game,
+    };
+    serde_json::to_string(&file).unwrap_or_else(|_| "{}".to_string())
+}
+
+pub fn load_game(data: &str) -> Result<Game, SaveError> {
+    let mut file: Value =
+        serde_json::from_str(data).map_err(|e| SaveError::Malformed(e.to_string()))?;
+    let version = file
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| SaveError::Malformed("missing version".to_string()))?;
+    if version == 0 || version > SAVE_VERSION as u64 {
+        return Err(SaveError::UnsupportedVersion {
+            found: version,
+            supported: SAVE_VERSION,
+        });
+    }
+
+    let mut game = file
+        .get_mut("game")
+        .map(Value::take)
+        .ok_or_else(|| SaveError::Malformed("missing game".to_string()))?;
+    for migrate in &MIGRATIONS[version as usize - 1..] {
+        migrate(&mut game);
+    }
+    serde_json::from_value(game).map_err(|e| SaveError::Malformed(e.to_string()))
+}
+
+/// Serialises a `ChaCha8Rng` as its key, stream and word position, so a
+/// loaded generator continues exactly where the saved one stopped. The
+/// 128-bit position is kept as a string to survive JavaScript numbers.
+pub mod rng_state {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct RngState {
+        seed: String,
+        stream: u64,
+        word_pos: String,
+    }
+
+    pub fn serialize<S: Serializer>(rng: &ChaCha8Rng, serializer: S) -> Result<S::Ok, S::Error> {
+        let seed = rng
+            .get_seed()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        RngState {
+            seed,
+            stream: rng.get_stream(),
+            word_pos: rng.get_word_pos().to_string(),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ChaCha8Rng, D::Error> {
+        let state = RngState::deserialize(deserializer)?;
+        if state.seed.len() != 64 {
+            return Err(D::Error::custom("rng seed must be 32 hex bytes"));
+        }
+        let mut seed = [0u8; 32];
+        for (i, byte) in seed.iter_mut().enumerate() {
+            *byte =
+                u8::from_str_radix(&state.seed[i * 2..i * 2 + 2], 16).map_err(D::Error::custom)?;
+        }
+        let word_pos: u128 = state.word_pos.parse().map_err(D::Error::custom)?;
+
+        let mut rng = ChaCha8Rng::from_seed(seed);
+        rng.set_stream(state.stream);
+        rng.set_word_pos(word_pos);
+        Ok(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ai::{AiPlayer, ExplorerAi};
+    use crate::game::player::PlayerId;
+
+    fn play(game: &mut Game, ais: &mut [ExplorerAi], turns: u32) {
+        for _ in 0..turns {
+            for (idx, ai) in ais.iter_mut().enumerate() {
+                for order in ai.issue_orders(game, idx as PlayerId) {
+                    let _ = game.submit(idx as PlayerId, order);
+                }
+            }
+            game.tick();
+        }
+    }
+
+    #[test]
+    fn loaded_games_continue_identically() {
+        let mut original = Game::with_factions(2718, 2);
+        let mut ais = vec![ExplorerAi::new(), ExplorerAi::new()];
+        play(&mut original, &mut ais, 2);
+        for (idx, ai) in ais.iter_mut().enumerate() {
+            for order in ai.issue_orders(&original, idx as PlayerId) {
+                let _ = original.submit(idx as PlayerId, order);
+            }
+        }
+        assert!(!original.pending_orders().is_empty());
+
+        let saved = save_game(&original);
+        let mut restored = load_game(&saved).expect("save loads");
+        assert_eq!(save_game(&restored), saved);
+
+        let mut restored_ais = ais.clone();
+        assert_eq!(original.tick(), restored.tick());
+        play(&mut original, &mut ais, 12);
+        play(&mut restored, &mut restored_ais, 12);
+        assert_eq!(save_game(&original), save_game(&restored));
+    }
+
+    /// Saves written by the last release on each `SAVE_VERSION`, all from
+    /// seed 2718 with two `ExplorerAi` factions after twelve turns.
+    const FIXTURES: &[&str] = &[
+        include_str!("../../tests/fixtures/saves/v1.json"),
+        include_str!("../../tests/fixtures/saves/v2.json"),
+        include_str!("../../tests/fixtures/saves/v3.json"),
+        include_str!("../../tests/fixtures/saves/v4.json"),
+        include_str!("../../tests/fixtures/saves/v5.json"),
+        include_str!("../../tests/fixtures/saves/v6.json"),
+        include_str!("../../tests/fixtures/saves/v7.json"),
+    ];
+
+    #[test]
+    fn every_past_version_has_a_fixture() {
+        assert_eq!(FIXTURES.len(), SAVE_VERSION as usize);
+        for (idx, fixture) in FIXTURES.iter().enumerate() {
+            let file: Value = serde_json::from_str(fixture).unwrap();
+            assert_eq!(file["version"], idx + 1);
+        }
+    }
+
+    #[test]
+    fn version_one_saves_load_without_an_order_log() {
+        let loaded = load_game(FIXTURES[0]).expect("v1 save loads");
+        assert!(loaded.order_log().is_none());
+        assert_eq!(loaded.turn(), 12);
+        assert_eq!(loaded.players().len(), 2);
+    }
+
+    #[test]
+    fn saves_from_every_version_load_and_keep_playing() {
+        for (idx, fixture) in FIXTURES.iter().enumerate() {
+            let mut loaded =
+                load_game(fixture).unwrap_or_else(|e| panic!("v{} save: {}", idx + 1, e));
+            assert_eq!(loaded.turn(), 12, "v{}", idx + 1);
+            assert_eq!(loaded.order_log().is_some(), idx > 0, "v{}", idx + 1);
+
+            let resaved = save_game(&loaded);
+            assert_eq!(save_game(&load_game(&resaved).unwrap()), resaved);
+
+            let mut ais = vec![ExplorerAi::new(), ExplorerAi::new()];
+            play(&mut loaded, &mut ais, 3);
+            assert_eq!(loaded.turn(), 15);
+        }
+    }
+
+    /// Written by the last build to generate maps from one stream per
+    /// universe and to spend faction dice on names.
+    const BEFORE_PER_SYSTEM_SEEDS: &str =
+        include_str!("../../tests/fixtures/saves/v5-before-per-system-seeds.json");
+
+    fn assert_rewinds_to_every_turn(loaded: &Game) {
+        let log = loaded.order_log().expect("saves since v2 keep a log");
+        assert_eq!(log.universe.as_ref(), Some(loaded.universe()));
+        for turn in (0..loaded.turn()).rev() {
+            let mut game = loaded.clone();
+            game.rewind(turn)
+                .unwrap_or_else(|e| panic!("rewind to {}: {}", turn, e));
+            assert_eq!(game.turn(), turn);
+        }
+    }
+
+    #[test]
+    fn version_five_saves_rewind_onto_the_map_they_were_played_on() {
+        let loaded = load_game(BEFORE_PER_SYSTEM_SEEDS).expect("v5 save loads");
+        let log = loaded.order_log().expect("v5 saves keep a log");
+        let names: Vec<&str> = log.starts.iter().map(|s| s.name.as_str()).collect();
+        let saved: Vec<&str> = loaded.players().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, saved);
+        assert_rewinds_to_every_turn(&loaded);
+
+        let resaved = save_game(&loaded);
+        assert_eq!(save_game(&load_game(&resaved).unwrap()), resaved);
+    }
+
+    #[test]
+    fn version_six_saves_keep_their_nicknames_on_rewind() {
+        let loaded = load_game(FIXTURES[5]).expect("v6 save loads");
+        assert!(loaded.order_log().unwrap().starts.is_empty());
+        assert_rewinds_to_every_turn(&loaded);
+    }
+
+    #[test]
+    fn rejects_unknown_versions_and_garbage() {
+        let saved = save_game(&Game::new(1));
+        let future = saved.replacen(
+            &format!("\"version\":{}", SAVE_VERSION),
+            &format!("\"version\":{}", SAVE_VERSION + 1),
+            1,
+        );
+        assert_eq!(
+            load_game(&future).err(),
+            Some(SaveError::UnsupportedVersion {
+                found: SAVE_VERSION as u64 + 1,
+                supported: SAVE_VERSION,
+            })
+        );
+        assert!(matches!(
+            load_game("{\"version\":5}"),
+            Err(SaveError::Malformed(_))
+        ));
+        assert!(matches!(
+            load_game("not json"),
+            Err(SaveError::Malformed(_))
+        ));
+    }
+}
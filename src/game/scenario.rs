@@ -0,0 +1,639 @@
+use crate::game::hazard::{hazard_profile, Hazard, HazardKind, HazardProfile};
+use crate::game::naming::NamingConfig;
+use crate::game::system::{
+    OrbitalBody, OrbitalKind, Star, StarSystem, Universe, UniverseConfig, UniverseGenerator,
+};
+use crate::game::victory::VictoryConfig;
+use crate::game::Game;
+use serde::Deserialize;
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+/// A hand-authored universe, such as a fixed tutorial map. Anything left
+/// out is filled in from the seed the way the generator would have made
+/// it: a system without stars or orbitals gets generated ones, and a body
+/// without a name, orbit, hazards or moons gets those rolled. Nicknames are
+/// never invented for authored stars and bodies. A saved `Universe` is
+/// itself a complete universe file.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UniverseFile {
+    pub systems: Vec<SystemFile>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SystemFile {
+    /// Must match the system's position in the file when given.
+    pub id: Option<u32>,
+    pub stars: Option<Vec<StarFile>>,
+    pub orbitals: Option<Vec<BodyFile>>,
+    /// Links need only be listed on one end.
+    #[serde(default)]
+    pub links: Vec<u32>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StarFile {
+    pub id: Option<u32>,
+    pub name: Option<String>,
+    pub nickname: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BodyFile {
+    pub id: Option<u32>,
+    pub name: Option<String>,
+    pub nickname: Option<String>,
+    pub distance: Option<u32>,
+    pub yield_rate: Option<u32>,
+    pub hazards: Option<Vec<HazardFile>>,
+    /// Planetoid at the top level and moon under another body by default.
+    pub kind: Option<OrbitalKind>,
+    pub moons: Option<Vec<BodyFile>>,
+}
+
+/// A hazard by kind alone, with its usual profile, or with a profile of its
+/// own.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum HazardFile {
+    Kind(HazardKind),
+    Full {
+        kind: HazardKind,
+        profile: Option<HazardProfile>,
+    },
+}
+
+impl HazardFile {
+    fn hazard(&self) -> Hazard {
+        match *self {
+            HazardFile::Kind(kind)
+            | HazardFile::Full {
+                kind,
+                profile: None,
+            } => Hazard {
+                kind,
+                profile: hazard_profile(kind),
+            },
+            HazardFile::Full {
+                kind,
+                profile: Some(profile),
+            } => Hazard { kind, profile },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UniverseError {
+    Malformed(String),
+    Empty,
+    MisnumberedSystem { index: usize, id: u32 },
+    UnknownLink { system: u32, to: u32 },
+    SelfLink { system: u32 },
+    Disconnected { system: u32 },
+    DuplicateName(String),
+    DuplicateId(u32),
+    DuplicateHazard { body: String, kind: HazardKind },
+    IllegalHazard { body: String, kind: HazardKind },
+    TooFewSystems { systems: usize, factions: usize },
+}
+
+impl fmt::Display for UniverseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UniverseError::Malformed(msg) => write!(f, "malformed universe: {}", msg),
+            UniverseError::Empty => write!(f, "the universe has no systems"),
+            UniverseError::MisnumberedSystem { index, id } => {
+                write!(f, "system {} is listed at position {}", id, index)
+            }
+            UniverseError::UnknownLink { system, to } => {
+                write!(f, "system {} links to unknown system {}", system, to)
+            }
+            UniverseError::SelfLink { system } => write!(f, "system {} links to itself", system),
+            UniverseError::Disconnected { system } => {
+                write!(f, "system {} cannot be reached from system 0", system)
+            }
+            UniverseError::DuplicateName(name) => write!(f, "\"{}\" is used twice", name),
+            UniverseError::DuplicateId(id) => write!(f, "id {} is used twice", id),
+            UniverseError::DuplicateHazard { body, kind } => {
+                write!(f, "{} has {:?} more than once", body, kind)
+            }
+            UniverseError::IllegalHazard { body, kind } => {
+                write!(f, "{:?} on {} has a profile outside 0 to 1", kind, body)
+            }
+            UniverseError::TooFewSystems { systems, factions } => write!(
+                f,
+                "{} factions need at least as many systems, found {}",
+                factions, systems
+            ),
+        }
+    }
+}
+
+/// Reads a universe file, fills its gaps from `seed` and checks the result.
+pub fn load_universe(json: &str, seed: u64) -> Result<Universe, UniverseError> {
+    let file: UniverseFile =
+        serde_json::from_str(json).map_err(|e| UniverseError::Malformed(e.to_string()))?;
+    complete_universe(file, seed)
+}
+
+/// Starts a game for `factions` players on the universe in `json`.
+pub fn load_scenario(json: &str, seed: u64, factions: usize) -> Result<Game, UniverseError> {
+    let universe = load_universe(json, seed)?;
+    if universe.systems.len() < factions {
+        return Err(UniverseError::TooFewSystems {
+            systems: universe.systems.len(),
+            factions,
+        });
+    }
+    Ok(Game::with_universe(
+        seed,
+        factions,
+        VictoryConfig::default(),
+        NamingConfig::default(),
+        universe,
+    ))
+}
+
+pub fn complete_universe(file: UniverseFile, seed: u64) -> Result<Universe, UniverseError> {
+    let n = file.systems.len();
+    if n == 0 {
+        return Err(UniverseError::Empty);
+    }
+    let mut links: Vec<Vec<u32>> = vec![Vec::new(); n];
+    for (index, system) in file.systems.iter().enumerate() {
+        let id = index as u32;
+        if let Some(given) = system.id.filter(|&given| given != id) {
+            return Err(UniverseError::MisnumberedSystem { index, id: given });
+        }
+        for &to in &system.links {
+            if to == id {
+                return Err(UniverseError::SelfLink { system: id });
+            }
+            if to as usize >= n {
+                return Err(UniverseError::UnknownLink { system: id, to });
+            }
+            if !links[index].contains(&to) {
+                links[index].push(to);
+            }
+        }
+    }
+    // Authored links keep their order; the missing halves go after them.
+    for from in 0..n {
+        for to in links[from].clone() {
+            if !links[to as usize].contains(&(from as u32)) {
+                links[to as usize].push(from as u32);
+            }
+        }
+    }
+
+    let mut gen = UniverseGenerator::with_config(
+        seed,
+        UniverseConfig {
+            systems: n,
+            ..Default::default()
+        },
+    );
+    let (names, nicknames, ids) = authored(&file);
+    gen.reserve(names, nicknames, ids.map_or(0, |max| max + 1));
+
+    let mut systems: Vec<StarSystem> = file
+        .systems
+        .into_iter()
+        .zip(links)
+        .enumerate()
+        .map(|(index, (system, links))| fill_system(&mut gen, index as u32, system, links))
+        .collect();
+    gen.assign_nicknames(&mut systems);
+    let universe = Universe { systems };
+    validate_universe(&universe)?;
+    Ok(universe)
+}
+
+/// Names, nicknames and the highest id the file spells out.
+fn authored(file: &UniverseFile) -> (Vec<String>, Vec<String>, Option<u32>) {
+    let mut names = Vec::new();
+    let mut nicknames = Vec::new();
+    let mut max_id = None;
+    let mut note = |id: Option<u32>, name: &Option<String>, nickname: &Option<String>| {
+        names.extend(name.clone());
+        nicknames.extend(nickname.clone());
+        max_id = max_id.max(id);
+    };
+    for system in &file.systems {
+        for star in system.stars.iter().flatten() {
+            note(star.id, &star.name, &star.nickname);
+        }
+        let mut all = Vec::new();
+        flatten(system.orbitals.as_deref().unwrap_or_default(), &mut all);
+        for body in all {
+            note(body.id, &body.name, &body.nickname);
+        }
+    }
+    (names, nicknames, max_id)
+}
+
+/// Every body in `bodies`, moons and all.
+fn flatten<'a>(bodies: &'a [BodyFile], out: &mut Vec<&'a BodyFile>) {
+    for body in bodies {
+        out.push(body);
+        flatten(body.moons.as_deref().unwrap_or_default(), out);
+    }
+}
+
+fn fill_system(
+    gen: &mut UniverseGenerator,
+    id: u32,
+    system: SystemFile,
+    links: Vec<u32>,
+) -> StarSystem {
+    let path = format!("system/{}", id);
+    let (star_count, planetoid_count, asteroid_count) = gen.layout(id);
+
+    let stars: Vec<Star> = match system.stars.filter(|stars| !stars.is_empty()) {
+        Some(stars) => stars
+            .into_iter()
+            .map(|star| Star {
+                name: star.name.unwrap_or_else(|| gen.next_star_name(id)),
+                nickname: star.nickname,
+                id: star.id.unwrap_or_else(|| gen.alloc_id()),
+            })
+            .collect(),
+        None => (0..star_count)
+            .map(|i| gen.make_star(id, &format!("{}/star/{}", path, i)))
+            .collect(),
+    };
+    let primary = stars[0].name.clone();
+
+    let orbitals = match system.orbitals {
+        Some(bodies) => {
+            let mut counts = [0usize; 3];
+            bodies
+                .into_iter()
+                .map(|body| {
+                    let kind = body.kind.clone().unwrap_or(OrbitalKind::Planetoid);
+                    let slot = &mut counts[slot_index(&kind)];
+                    let i = *slot;
+                    *slot += 1;
+                    let default = |k: usize| match kind {
+                        OrbitalKind::Planetoid => {
+                            format!("{} {}", primary, (b'b' + k as u8) as char)
+                        }
+                        OrbitalKind::AsteroidBelt => {
+                            format!("{} Belt {}", primary, UniverseGenerator::roman_numeral(k))
+                        }
+                        OrbitalKind::Moon => {
+                            format!("{} {}", primary, UniverseGenerator::roman_numeral(k))
+                        }
+                    };
+                    let name = match body.name.clone() {
+                        Some(name) => name,
+                        None => gen.claim_name((i..).map(default)),
+                    };
+                    let body_path = format!("{}/{}/{}", path, slot_name(&kind), i);
+                    fill_body(gen, body, kind, name, &body_path)
+                })
+                .collect()
+        }
+        None => {
+            let mut orbitals = Vec::with_capacity(planetoid_count + asteroid_count);
+            for i in 0..planetoid_count {
+                let suffix = (b'b' + i as u8) as char;
+                let body_path = format!("{}/planetoid/{}", path, i);
+                orbitals.push(gen.make_planetoid(&primary, suffix, &body_path));
+            }
+            for i in 0..asteroid_count {
+                let body_path = format!("{}/belt/{}", path, i);
+                orbitals.push(gen.make_asteroid(&primary, i, &body_path));
+            }
+            orbitals
+        }
+    };
+
+    StarSystem {
+        id,
+        stars,
+        orbitals,
+        links,
+    }
+}
+
+fn slot_index(kind: &OrbitalKind) -> usize {
+    match kind {
+        OrbitalKind::Planetoid => 0,
+        OrbitalKind::AsteroidBelt => 1,
+        OrbitalKind::Moon => 2,
+    }
+}
+
+/// The generator's path segment for bodies of `kind`.
+fn slot_name(kind: &OrbitalKind) -> &'static str {
+    match kind {
+        OrbitalKind::Planetoid => "planetoid",
+        OrbitalKind::AsteroidBelt => "belt",
+        OrbitalKind::Moon => "moon",
+    }
+}
+
+/// `name` is the authored one, or a default no other body has taken.
+fn fill_body(
+    gen: &mut UniverseGenerator,
+    body: BodyFile,
+    kind: OrbitalKind,
+    name: String,
+    path: &str,
+) -> OrbitalBody {
+    let hazards = match &body.hazards {
+        Some(hazards) => hazards.iter().map(HazardFile::hazard).collect(),
+        None => gen.hazards_for_body(path),
+    };
+    let moons = match body.moons {
+        Some(moons) => moons
+            .into_iter()
+            .enumerate()
+            .map(|(k, moon)| {
+                let kind = moon.kind.clone().unwrap_or(OrbitalKind::Moon);
+                let moon_name = match moon.name.clone() {
+                    Some(moon_name) => moon_name,
+                    None => gen.claim_name(
+                        (k..).map(|j| format!("{} {}", name, UniverseGenerator::roman_numeral(j))),
+                    ),
+                };
+                fill_body(gen, moon, kind, moon_name, &format!("{}/moon/{}", path, k))
+            })
+            .collect(),
+        None if kind == OrbitalKind::Planetoid => gen.make_moons(&name, path),
+        None => Vec::new(),
+    };
+    let (distance, yield_rate) = gen.orbit(&kind, path);
+
+    OrbitalBody {
+        id: body.id.unwrap_or_else(|| gen.alloc_id()),
+        name,
+        nickname: body.nickname,
+        distance: body.distance.unwrap_or(distance),
+        yield_rate: body.yield_rate.unwrap_or(yield_rate),
+        hazards,
+        kind,
+        moons,
+    }
+}
+
+/// Checks what the rest of the game relies on: systems numbered by
+/// position, links that go both ways and join every system, one owner per
+/// id and name, and at most one hazard of each kind per body, with every
+/// chance between 0 and 1.
+pub fn validate_universe(universe: &Universe) -> Result<(), UniverseError> {
+    let n = universe.systems.len();
+    if n == 0 {
+        return Err(UniverseError::Empty);
+    }
+    for (index, system) in universe.systems.iter().enumerate() {
+        if system.id as usize != index {
+            return Err(UniverseError::MisnumberedSystem {
+                index,
+                id: system.id,
+            });
+        }
+        for &to in &system.links {
+            if to == system.id {
+                return Err(UniverseError::SelfLink { system: to });
+            }
+            let back = universe
+                .system(to)
+                .map(|other| other.links.contains(&system.id));
+            if back != Some(true) {
+                return Err(UniverseError::UnknownLink {
+                    system: system.id,
+                    to,
+                });
+            }
+        }
+    }
+
+    let mut reached = vec![false; n];
+    let mut queue = VecDeque::from([0usize]);
+    reached[0] = true;
+    while let Some(at) = queue.pop_front() {
+        for &next in &universe.systems[at].links {
+            if !std::mem::replace(&mut reached[next as usize], true) {
+                queue.push_back(next as usize);
+            }
+        }
+    }
+    if let Some(system) = reached.iter().position(|&r| !r) {
+        return Err(UniverseError::Disconnected {
+            system: system as u32,
+        });
+    }
+
+    let mut names = HashSet::new();
+    let mut ids = HashSet::new();
+    let mut claim = |id: u32, name: &str| {
+        if !ids.insert(id) {
+            return Err(UniverseError::DuplicateId(id));
+        }
+        if !names.insert(name.to_string()) {
+            return Err(UniverseError::DuplicateName(name.to_string()));
+        }
+        Ok(())
+    };
+    for system in &universe.systems {
+        for star in &system.stars {
+            claim(star.id, &star.name)?;
+        }
+    }
+    for body in universe.bodies() {
+        claim(body.id, &body.name)?;
+        let mut kinds = HashSet::new();
+        for hazard in &body.hazards {
+            let body = body.name.clone();
+            if !kinds.insert(hazard.kind) {
+                return Err(UniverseError::DuplicateHazard {
+                    body,
+                    kind: hazard.kind,
+                });
+            }
+            let HazardProfile {
+                probe_fail,
+                hull_damage,
+                yield_penalty,
+            } = hazard.profile;
+            if ![probe_fail, hull_damage, yield_penalty]
+                .iter()
+                .all(|p| (0.0..=1.0).contains(p))
+            {
+                return Err(UniverseError::IllegalHazard {
+                    body,
+                    kind: hazard.kind,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ai::{AiPlayer, ExplorerAi};
+    use crate::game::player::PlayerId;
+    use crate::game::replay::replay;
+    use crate::game::save::{load_game, save_game};
+
+    const TUTORIAL: &str = r#"{"systems": [
+        {"stars": [{"name": "Tutoria", "nickname": "The Cradle"}],
+         "orbitals": [
+            {"name": "Tutoria Prime", "distance": 80, "yield_rate": 3,
+             "hazards": ["Radiation"], "moons": []},
+            {"kind": "AsteroidBelt", "hazards": [{"kind": "Pirates",
+             "profile": {"probe_fail": 0.9, "hull_damage": 0.2, "yield_penalty": 0.0}}]}
+         ],
+         "links": [1]},
+        {"links": [2]},
+        {"stars": [{}], "orbitals": []}
+    ]}"#;
+
+    #[test]
+    fn sparse_files_fill_in_from_the_seed() {
+        let universe = load_universe(TUTORIAL, 11).expect("tutorial loads");
+        assert_eq!(universe, load_universe(TUTORIAL, 11).unwrap());
+        let links: Vec<_> = universe.systems.iter().map(|s| s.links.clone()).collect();
+        assert_eq!(links, vec![vec![1], vec![2, 0], vec![1]]);
+
+        let home = &universe.systems[0];
+        assert_eq!(home.stars.len(), 1);
+        assert_eq!(home.stars[0].nickname.as_deref(), Some("The Cradle"));
+        let prime = &home.orbitals[0];
+        assert_eq!((prime.distance, prime.yield_rate), (80, 3));
+        assert_eq!(
+            prime.hazards[0].profile,
+            hazard_profile(HazardKind::Radiation)
+        );
+        assert!(prime.moons.is_empty());
+        let belt = &home.orbitals[1];
+        assert_eq!(belt.name, "Tutoria Belt I");
+        assert_eq!(belt.hazards[0].profile.probe_fail, 0.9);
+        assert!((300..=900).contains(&belt.distance));
+
+        assert!(!universe.systems[1].stars.is_empty());
+        assert!(!universe.systems[1].orbitals.is_empty());
+        assert!(universe.systems[2].orbitals.is_empty());
+        assert_ne!(universe.systems[2].stars[0].name, "Tutoria");
+
+        let reseeded = load_universe(TUTORIAL, 12).unwrap();
+        assert_eq!(reseeded.systems[0].stars, home.stars);
+        assert_eq!(reseeded.systems[0].orbitals[0], *prime);
+        assert_ne!(reseeded.systems[1], universe.systems[1]);
+
+        let generated = UniverseGenerator::new(5).generate();
+        let json = serde_json::to_string(&generated).unwrap();
+        assert_eq!(load_universe(&json, 99), Ok(generated));
+    }
+
+    #[test]
+    fn default_names_step_around_authored_ones() {
+        let file = r#"{"systems": [{"stars": [{"name": "Sol"}], "orbitals": [
+            {"moons": []},
+            {"name": "Sol b", "moons": [{}, {"name": "Sol b I"}]},
+            {}
+        ]}]}"#;
+        let universe = load_universe(file, 3).expect("defaults give way to authored names");
+        let names = |bodies: &[OrbitalBody]| -> Vec<String> {
+            bodies.iter().map(|b| b.name.clone()).collect()
+        };
+        let sol = &universe.systems[0];
+        assert_eq!(names(&sol.orbitals), ["Sol c", "Sol b", "Sol d"]);
+        assert_eq!(names(&sol.orbitals[1].moons), ["Sol b II", "Sol b I"]);
+    }
+
+    #[test]
+    fn broken_files_are_rejected() {
+        let cases = [
+            (r#"{"systems": []}"#, UniverseError::Empty),
+            (
+                r#"{"systems": [{"id": 1}, {"links": [0]}]}"#,
+                UniverseError::MisnumberedSystem { index: 0, id: 1 },
+            ),
+            (
+                r#"{"systems": [{"links": [3]}, {}]}"#,
+                UniverseError::UnknownLink { system: 0, to: 3 },
+            ),
+            (
+                r#"{"systems": [{"links": [0]}]}"#,
+                UniverseError::SelfLink { system: 0 },
+            ),
+            (
+                r#"{"systems": [{"links": [1]}, {}, {}]}"#,
+                UniverseError::Disconnected { system: 2 },
+            ),
+            (
+                r#"{"systems": [{"stars": [{"name": "Twin"}], "orbitals": [], "links": [1]},
+                                {"stars": [{"name": "Twin"}], "orbitals": []}]}"#,
+                UniverseError::DuplicateName("Twin".to_string()),
+            ),
+            (
+                r#"{"systems": [{"stars": [{"id": 3}, {"id": 3}], "orbitals": []}]}"#,
+                UniverseError::DuplicateId(3),
+            ),
+            (
+                r#"{"systems": [{"orbitals": [{"name": "Rock", "hazards": ["Debris", "Debris"]}]}]}"#,
+                UniverseError::DuplicateHazard {
+                    body: "Rock".to_string(),
+                    kind: HazardKind::Debris,
+                },
+            ),
+            (
+                r#"{"systems": [{"orbitals": [{"name": "Rock", "hazards": [{"kind": "Pirates",
+                    "profile": {"probe_fail": 1.5, "hull_damage": 0.0, "yield_penalty": 0.0}}]}]}]}"#,
+                UniverseError::IllegalHazard {
+                    body: "Rock".to_string(),
+                    kind: HazardKind::Pirates,
+                },
+            ),
+        ];
+        for (json, expected) in cases {
+            assert_eq!(load_universe(json, 1), Err(expected), "{}", json);
+        }
+        assert!(matches!(
+            load_universe(r#"{"systems": [{"planets": []}]}"#, 1),
+            Err(UniverseError::Malformed(_))
+        ));
+        assert_eq!(
+            load_scenario(r#"{"systems": [{}]}"#, 1, 2).err(),
+            Some(UniverseError::TooFewSystems {
+                systems: 1,
+                factions: 2
+            })
+        );
+    }
+
+    #[test]
+    fn scenario_games_replay_on_their_own_map() {
+        let mut game = load_scenario(TUTORIAL, 7, 2).expect("tutorial starts");
+        let universe = load_universe(TUTORIAL, 7).unwrap();
+        assert_eq!(game.universe(), &universe);
+
+        let mut ais = [ExplorerAi::new(), ExplorerAi::new()];
+        for _ in 0..12 {
+            for (idx, ai) in ais.iter_mut().enumerate() {
+                for order in ai.issue_orders(&game, idx as PlayerId) {
+                    let _ = game.submit(idx as PlayerId, order);
+                }
+            }
+            game.tick();
+        }
+
+        let log = game.order_log().expect("scenario games keep a log").clone();
+        assert_eq!(log.universe.as_ref(), Some(&universe));
+        let replayed = replay(&log, 12).expect("replay matches");
+        assert_eq!(replayed.checksum(), game.checksum());
+
+        let mut loaded = load_game(&save_game(&game)).expect("save loads");
+        loaded.rewind(0).expect("rewinds to the start");
+        assert_eq!(loaded.universe(), &universe);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::game::economy::colony_income;
+use crate::game::event::Event;
+use crate::game::fleet::{Fleet, FleetId};
+use crate::game::player::{Player, PlayerId};
+use crate::game::tech::Tech;
+use crate::game::victory::{progress, score, ConditionProgress, Score, Victory};
+use crate::game::Game;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct PlayerStatus {
+    id: PlayerId,
+    name: String,
+    credits: f64,
+    /// What the player's colonies will pay out at the end of the next tick.
+    income: f64,
+    colonies: usize,
+    pending_orders: usize,
+    techs: Vec<Tech>,
+}
+
+#[derive(Serialize)]
+pub struct FleetSummary {
+    id: FleetId,
+    owner: PlayerId,
+    system: u32,
+    destination: Option<u32>,
+    eta: Option<u32>,
+    ships: usize,
+    integrity: f64,
+    probes: u32,
+}
+
+impl From<&Fleet> for FleetSummary {
+    fn from(fleet: &Fleet) -> Self {
+        Self {
+            id: fleet.id,
+            owner: fleet.owner,
+            system: fleet.system,
+            destination: fleet.transit.map(|t| t.to).or(fleet.route.last().copied()),
+            eta: fleet.eta(),
+            ships: fleet.ships.len(),
+            integrity: fleet.ships.iter().map(|s| s.integrity).sum(),
+            probes: fleet.probes,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PlayerProgress {
+    player: PlayerId,
+    conditions: Vec<ConditionProgress>,
+    score: Score,
+}
+
+#[derive(Serialize)]
+pub struct VictoryStatus<'a> {
+    outcome: Option<&'a Victory>,
+    players: Vec<PlayerProgress>,
+}
+
+/// Everything the HUD shows, from one player's seat or from above.
+#[derive(Serialize)]
+pub struct StatusView<'a> {
+    turn: u32,
+    players: Vec<PlayerStatus>,
+    fleets: Vec<FleetSummary>,
+    /// Events published by the latest tick.
+    events: Vec<&'a Event>,
+    victory: VictoryStatus<'a>,
+}
+
+impl<'a> StatusView<'a> {
+    pub fn seen_by(game: &'a Game, viewer: Option<PlayerId>) -> Self {
+        let shown = |id: PlayerId| viewer.is_none_or(|v| v == id);
+        let colonies = |id: PlayerId| game.colonies().iter().filter(move |c| c.owner == id);
+        let players: Vec<&Player> = game.players().iter().filter(|p| shown(p.id)).collect();
+
+        Self {
+            turn: game.turn(),
+            players: players
+                .iter()
+                .map(|p| PlayerStatus {
+                    id: p.id,
+                    name: p.name.clone(),
+                    credits: p.stockpile.credits,
+                    income: colonies(p.id)
+                        .filter_map(|c| {
+                            let (_, body) = game.universe().locate_body(c.body)?;
+                            Some(colony_income(c, body, &p.research.modifiers()))
+                        })
+                        .sum(),
+                    colonies: colonies(p.id).count(),
+                    pending_orders: game
+                        .pending_orders()
+                        .iter()
+                        .filter(|o| o.player == p.id)
+                        .count(),
+                    techs: p.research.unlocked().collect(),
+                })
+                .collect(),
+            fleets: game
+                .fleets()
+                .iter()
+                .filter(|f| shown(f.owner))
+                .map(FleetSummary::from)
+                .collect(),
+            events: game
+                .events()
+                .turn(game.turn())
+                .iter()
+                .filter(|e| e.seen_by(viewer))
+                .collect(),
+            victory: VictoryStatus {
+                outcome: game.outcome(),
+                players: players
+                    .iter()
+                    .map(|p| PlayerProgress {
+                        player: p.id,
+                        conditions: game
+                            .victory_config()
+                            .conditions
+                            .iter()
+                            .map(|c| progress(game, p.id, c))
+                            .collect(),
+                        score: score(game, p.id),
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+pub fn status_json(game: &Game, viewer: Option<PlayerId>) -> String {
+    serde_json::to_string(&StatusView::seen_by(game, viewer)).unwrap_or_else(|_| "{}".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::order::Order;
+    use serde_json::Value;
+
+    #[test]
+    fn status_covers_the_hud_for_one_seat_or_all() {
+        let mut game = Game::with_factions(21, 2);
+        let home = game.players()[1].home_system;
+        let link = game.universe().systems[home as usize].links[0];
+        game.submit(1, Order::MoveFleet { fleet: 1, to: link })
+            .unwrap();
+
+        let all: Value = serde_json::from_str(&status_json(&game, None)).unwrap();
+        assert_eq!(all["turn"], 0);
+        assert_eq!(all["players"].as_array().unwrap().len(), 2);
+        assert_eq!(all["players"][1]["pending_orders"], 1);
+        assert_eq!(all["fleets"].as_array().unwrap().len(), 2);
+
+        game.tick();
+        let seat: Value = serde_json::from_str(&status_json(&game, Some(1))).unwrap();
+        assert_eq!(seat["turn"], 1);
+        assert_eq!(seat["players"].as_array().unwrap().len(), 1);
+        assert_eq!(seat["players"][0]["credits"], 25.0);
+        assert_eq!(seat["fleets"][0]["id"], 1);
+        assert_eq!(seat["fleets"][0]["system"], link);
+        let events = seat["events"].as_array().unwrap();
+        assert!(events.iter().any(|e| e["kind"] == "system_discovered"));
+        assert!(events.iter().all(|e| e["player"] == 1));
+        assert_eq!(seat["victory"]["outcome"], Value::Null);
+        let progress = &seat["victory"]["players"][0];
+        assert_eq!(progress["player"], 1);
+        assert_eq!(
+            progress["conditions"].as_array().unwrap().len(),
+            game.victory_config().conditions.len()
+        );
+        assert_eq!(progress["conditions"][0]["type"], "survey");
+        assert_eq!(progress["conditions"][0]["met"], false);
+        assert!(progress["score"]["exploration"].as_f64().unwrap() > 1.0);
+    }
+}
@@ -1,39 +1,48 @@
+use crate::game::fleet::FleetView;
 use crate::game::hazard::{
-    apply_hazard, hazard_label, hazard_profile, Hazard, HazardKind, RiskChannels,
+    accumulate_risk, accumulate_risk_with, hazard_label, hazard_profile, Hazard, HazardKind,
+    TechModifiers,
 };
-use crate::game::naming::{generate_nickname, generate_star_name};
+use crate::game::knowledge::{BodyIntel, Knowledge};
+use crate::game::name_filter::UsedNames;
+use crate::game::naming::{describe_body, describe_system, NamingConfig, StyleSequences};
+use crate::game::nickname::NicknameSpace;
+use crate::game::replay::Checksum;
 use rand::{seq::SliceRandom, Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
-use serde::Serialize;
-use std::collections::HashSet;
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::ops::RangeInclusive;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Star {
     pub id: u32,
     pub name: String,
     pub nickname: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum OrbitalKind {
     Planetoid,
     AsteroidBelt,
     Moon,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct OrbitalBody {
     pub id: u32,
     pub name: String,
     pub nickname: Option<String>,
     pub distance: u32,
+    /// Raw output per mine per turn, before hazard yield penalties.
+    pub yield_rate: u32,
     pub hazards: Vec<Hazard>,
     pub kind: OrbitalKind,
     pub moons: Vec<OrbitalBody>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct StarSystem {
     pub id: u32,
     pub stars: Vec<Star>,
@@ -69,6 +78,7 @@ pub struct UniverseConfig {
     pub systems: usize,
     pub extra_edges: usize,
     pub system: SystemConfig,
+    pub naming: NamingConfig,
 }
 
 impl Default for UniverseConfig {
@@ -77,18 +87,76 @@ impl Default for UniverseConfig {
             systems: 4,
             extra_edges: 2,
             system: SystemConfig::default(),
+            naming: NamingConfig::default(),
         }
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Universe {
     pub systems: Vec<StarSystem>,
 }
 
-const BASE_PROBE_FAILURE: f64 = 0.05;
+impl Universe {
+    pub fn system(&self, id: u32) -> Option<&StarSystem> {
+        self.systems.get(id as usize)
+    }
+
+    /// Every distinct hazard found on the system's bodies; fleets passing
+    /// through are exposed to all of them.
+    pub fn system_hazards(&self, id: u32) -> Vec<Hazard> {
+        let mut hazards: Vec<Hazard> = Vec::new();
+        let Some(system) = self.system(id) else {
+            return hazards;
+        };
+        let bodies = system
+            .orbitals
+            .iter()
+            .flat_map(|body| std::iter::once(body).chain(body.moons.iter()));
+        for hazard in bodies.flat_map(|body| body.hazards.iter()) {
+            if !hazards.iter().any(|h| h.kind == hazard.kind) {
+                hazards.push(*hazard);
+            }
+        }
+        hazards
+    }
+
+    /// Every planetoid, belt and moon in the universe.
+    pub fn bodies(&self) -> impl Iterator<Item = &OrbitalBody> {
+        self.systems.iter().flat_map(|sys| {
+            sys.orbitals
+                .iter()
+                .flat_map(|body| std::iter::once(body).chain(body.moons.iter()))
+        })
+    }
+
+    /// Finds a planetoid, belt or moon by id along with the system it orbits in.
+    pub fn locate_body(&self, body_id: u32) -> Option<(&StarSystem, &OrbitalBody)> {
+        self.systems.iter().find_map(|sys| {
+            sys.orbitals
+                .iter()
+                .flat_map(|body| std::iter::once(body).chain(body.moons.iter()))
+                .find(|body| body.id == body_id)
+                .map(|body| (sys, body))
+        })
+    }
+}
+
+pub const BASE_PROBE_FAILURE: f64 = 0.05;
+/// Builds a universe from a seed. Every system, body and purpose draws from
+/// its own generator, seeded from a hash of the seed and the entity's path
+/// ("system/3/planetoid/1/hazards"), so turning one config knob only moves
+/// the things that knob controls: more moons or more nicknames leave every
+/// other name and hazard where it was. Nicknames stay unique, so one can
+/// still move when another body claims it first.
 pub struct UniverseGenerator {
-    rng: ChaCha8Rng,
-    used_names: HashSet<String>,
+    seed: u64,
+    names: StyleSequences,
+    nicknames: Vec<(Vec<HazardKind>, NicknameSpace)>,
+    /// Bodies that rolled a nickname, as (roll, id, path, hazard kinds);
+    /// see `assign_nicknames`.
+    wanted: Vec<(f64, u32, String, Vec<HazardKind>)>,
+    used_names: UsedNames,
     used_nicknames: HashSet<String>,
     next_id: u32,
     config: UniverseConfig,
@@ -101,8 +169,11 @@ impl UniverseGenerator {
 
     pub fn with_config(seed: u64, config: UniverseConfig) -> Self {
         Self {
-            rng: ChaCha8Rng::seed_from_u64(seed),
-            used_names: HashSet::new(),
+            seed,
+            names: StyleSequences::new(&config.naming, seed),
+            nicknames: Vec::new(),
+            wanted: Vec::new(),
+            used_names: UsedNames::new(),
             used_nicknames: HashSet::new(),
             next_id: 0,
             config,
@@ -114,6 +185,7 @@ impl UniverseGenerator {
         for system_id in 0..self.config.systems as u32 {
             systems.push(self.generate_system(system_id));
         }
+        self.assign_nicknames(&mut systems);
         self.connect_graph(&mut systems);
 
         Universe { systems }
@@ -124,13 +196,14 @@ impl UniverseGenerator {
         if n <= 1 {
             return;
         }
+        let mut rng = stream(self.seed, "links");
 
         // Random spanning tree: shuffle node order and connect each new node to a random earlier node.
         let mut order: Vec<u32> = (0..n as u32).collect();
-        order.shuffle(&mut self.rng);
+        order.shuffle(&mut rng);
         for window in 1..order.len() {
             let child = order[window];
-            let parent_idx = self.rng.gen_range(0..window);
+            let parent_idx = rng.gen_range(0..window);
             let parent = order[parent_idx];
             systems[parent as usize].links.push(child);
             systems[child as usize].links.push(parent);
@@ -138,10 +211,10 @@ impl UniverseGenerator {
 
         // Add extra random bidirectional edges.
         for _ in 0..self.config.extra_edges {
-            let a = self.rng.gen_range(0..systems.len()) as u32;
-            let mut b = self.rng.gen_range(0..systems.len()) as u32;
+            let a = rng.gen_range(0..systems.len()) as u32;
+            let mut b = rng.gen_range(0..systems.len()) as u32;
             if a == b {
-                b = ((b + 1) % systems.len() as u32) as u32;
+                b = (b + 1) % systems.len() as u32;
             }
             if !systems[a as usize].links.contains(&b) {
                 systems[a as usize].links.push(b);
@@ -150,49 +223,131 @@ impl UniverseGenerator {
         }
     }
 
-    fn rng_in_range(&mut self, range: RangeInclusive<usize>) -> usize {
-        self.rng.gen_range(range)
+    /// Keeps hand-authored names and nicknames out of everything generated
+    /// from here on, and starts fresh ids after `first_free_id`.
+    pub(crate) fn reserve(
+        &mut self,
+        names: impl IntoIterator<Item = String>,
+        nicknames: impl IntoIterator<Item = String>,
+        first_free_id: u32,
+    ) {
+        self.used_names.extend(names);
+        self.used_nicknames.extend(nicknames);
+        self.next_id = self.next_id.max(first_free_id);
     }
 
-    fn maybe_nickname(&mut self, hazards: &[HazardKind]) -> Option<String> {
-        let roll: f64 = self.rng.gen();
+    /// The first of `candidates` that no star or body has yet, now taken.
+    pub(crate) fn claim_name(&mut self, candidates: impl IntoIterator<Item = String>) -> String {
+        candidates
+            .into_iter()
+            .find(|name| self.used_names.insert(name.clone()))
+            .expect("candidate names never run out")
+    }
+
+    pub(crate) fn stream(&self, path: &str) -> ChaCha8Rng {
+        stream(self.seed, path)
+    }
+
+    /// Notes that body `id` at `path` wants a nickname if its
+    /// `{path}/nickname` roll comes in under `nickname_chance`.
+    pub(crate) fn roll_nickname(&mut self, id: u32, path: &str, hazards: &[HazardKind]) {
+        let roll: f64 = self.stream(&format!("{}/nickname", path)).gen();
         if roll < self.config.system.nickname_chance {
-            generate_nickname(&mut self.rng, &mut self.used_nicknames, hazards)
-        } else {
-            None
+            let mut kinds = hazards.to_vec();
+            kinds.sort_by_key(|k| *k as u8);
+            kinds.dedup();
+            self.wanted.push((roll, id, path.to_string(), kinds));
         }
     }
 
-    fn make_star(&mut self) -> Star {
-        let name = generate_star_name(&mut self.rng, &mut self.used_names);
-        let nickname = self.maybe_nickname(&[]);
-        let id = self.next_id;
-        self.next_id += 1;
+    /// Gives every body that rolled a nickname one picked from its own
+    /// `{path}/nickname` stream. Lower rolls pick first, so a nickname only
+    /// moves when a body that rolled lower wants the same one: raising
+    /// `nickname_chance` or adding bodies that miss the roll leaves every
+    /// existing nickname alone. Bodies with the same hazards share one
+    /// `NicknameSpace`.
+    pub(crate) fn assign_nicknames(&mut self, systems: &mut [StarSystem]) {
+        let mut wanted = std::mem::take(&mut self.wanted);
+        wanted.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.2.cmp(&b.2)));
+        let mut picked = HashMap::with_capacity(wanted.len());
+        for (_, id, path, kinds) in wanted {
+            let mut rng = self.stream(&format!("{}/nickname", path));
+            let _roll: f64 = rng.gen();
+            let idx = match self.nicknames.iter().position(|(k, _)| *k == kinds) {
+                Some(idx) => idx,
+                None => {
+                    let lexicon = self.config.naming.nicknames.lexicon();
+                    let space = NicknameSpace::new(lexicon, &kinds);
+                    self.nicknames.push((kinds, space));
+                    self.nicknames.len() - 1
+                }
+            };
+            if let Some(nickname) = self.nicknames[idx]
+                .1
+                .pick(&mut rng, &mut self.used_nicknames)
+            {
+                picked.insert(id, nickname);
+            }
+        }
 
-        Star { id, name, nickname }
+        fn fill(body: &mut OrbitalBody, picked: &mut HashMap<u32, String>) {
+            if let Some(nickname) = picked.remove(&body.id) {
+                body.nickname = Some(nickname);
+            }
+            for moon in &mut body.moons {
+                fill(moon, picked);
+            }
+        }
+        for system in systems {
+            for star in &mut system.stars {
+                if let Some(nickname) = picked.remove(&star.id) {
+                    star.nickname = Some(nickname);
+                }
+            }
+            for body in &mut system.orbitals {
+                fill(body, &mut picked);
+            }
+        }
     }
 
-    fn next_hazard_kind(&mut self) -> HazardKind {
-        match self.rng.gen_range(0..3) {
+    /// Draws from one shuffled sequence per naming style, so naming never
+    /// slows down, repeats or runs out as the universe grows.
+    pub(crate) fn next_star_name(&mut self, system_id: u32) -> String {
+        self.names.next_system_name(system_id, &mut self.used_names)
+    }
+
+    pub(crate) fn make_star(&mut self, system_id: u32, path: &str) -> Star {
+        let name = self.next_star_name(system_id);
+        let id = self.alloc_id();
+        self.roll_nickname(id, path, &[]);
+
+        Star {
+            id,
+            name,
+            nickname: None,
+        }
+    }
+
+    fn next_hazard_kind(rng: &mut ChaCha8Rng) -> HazardKind {
+        match rng.gen_range(0..3) {
             0 => HazardKind::Radiation,
             1 => HazardKind::Pirates,
             _ => HazardKind::Debris,
         }
     }
 
-    fn hazards_for_body(&mut self) -> Vec<Hazard> {
+    pub(crate) fn hazards_for_body(&self, path: &str) -> Vec<Hazard> {
         if self.config.system.max_hazards_per_body == 0 {
             return Vec::new();
         }
+        let mut rng = self.stream(&format!("{}/hazards", path));
 
-        let count = self
-            .rng
-            .gen_range(0..=self.config.system.max_hazards_per_body as u32) as usize;
+        let count = rng.gen_range(0..=self.config.system.max_hazards_per_body as u32) as usize;
 
         let mut unique_kinds = HashSet::new();
         let mut hazards = Vec::with_capacity(count);
         while hazards.len() < count {
-            let kind = self.next_hazard_kind();
+            let kind = Self::next_hazard_kind(&mut rng);
             if unique_kinds.insert(kind) {
                 hazards.push(Hazard {
                     kind,
@@ -203,23 +358,29 @@ impl UniverseGenerator {
         hazards
     }
 
-    fn roman_numeral(idx: usize) -> String {
+    pub(crate) fn roman_numeral(idx: usize) -> String {
         const NUMS: &[&str] = &["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X"];
         NUMS.get(idx).unwrap_or(&"X").to_string()
     }
 
-    fn make_moons(&mut self, parent_name: &str) -> Vec<OrbitalBody> {
-        let moon_count = self.rng_in_range(self.config.system.moons_per_planetoid.clone());
+    pub(crate) fn make_moons(&mut self, parent_name: &str, parent_path: &str) -> Vec<OrbitalBody> {
+        let moon_count = self
+            .stream(&format!("{}/moons", parent_path))
+            .gen_range(self.config.system.moons_per_planetoid.clone());
         let mut moons = Vec::with_capacity(moon_count);
         for i in 0..moon_count {
+            let path = format!("{}/moon/{}", parent_path, i);
             let name = format!("{} {}", parent_name, Self::roman_numeral(i));
-            let hazards = self.hazards_for_body();
-            let nickname = self.maybe_nickname(&hazard_kinds(&hazards));
+            let hazards = self.hazards_for_body(&path);
+            let (distance, yield_rate) = self.orbit(&OrbitalKind::Moon, &path);
+            let id = self.alloc_id();
+            self.roll_nickname(id, &path, &hazard_kinds(&hazards));
             moons.push(OrbitalBody {
-                id: self.alloc_id(),
+                id,
                 name,
-                nickname,
-                distance: self.rng.gen_range(1..=20),
+                nickname: None,
+                distance,
+                yield_rate,
                 hazards,
                 kind: OrbitalKind::Moon,
                 moons: Vec::new(),
@@ -228,66 +389,99 @@ impl UniverseGenerator {
         moons
     }
 
-    fn alloc_id(&mut self) -> u32 {
+    /// Distance and yield for a body of `kind` at `path`.
+    pub(crate) fn orbit(&self, kind: &OrbitalKind, path: &str) -> (u32, u32) {
+        let mut rng = self.stream(&format!("{}/orbit", path));
+        match kind {
+            OrbitalKind::Planetoid => (rng.gen_range(40..=400), rng.gen_range(1..=5)),
+            OrbitalKind::AsteroidBelt => (rng.gen_range(300..=900), rng.gen_range(3..=6)),
+            OrbitalKind::Moon => (rng.gen_range(1..=20), rng.gen_range(1..=3)),
+        }
+    }
+
+    pub(crate) fn alloc_id(&mut self) -> u32 {
         let id = self.next_id;
         self.next_id += 1;
         id
     }
 
-    fn make_planetoid(&mut self, base_name: &str, suffix: char) -> OrbitalBody {
+    pub(crate) fn make_planetoid(
+        &mut self,
+        base_name: &str,
+        suffix: char,
+        path: &str,
+    ) -> OrbitalBody {
         let name = format!("{} {}", base_name, suffix);
-        let hazards = self.hazards_for_body();
-        let nickname = self.maybe_nickname(&hazard_kinds(&hazards));
-        let moons = self.make_moons(&name);
+        let hazards = self.hazards_for_body(path);
+        let moons = self.make_moons(&name, path);
+        let (distance, yield_rate) = self.orbit(&OrbitalKind::Planetoid, path);
+        let id = self.alloc_id();
+        self.roll_nickname(id, path, &hazard_kinds(&hazards));
 
         OrbitalBody {
-            id: self.alloc_id(),
+            id,
             name,
-            nickname,
-            distance: self.rng.gen_range(40..=400),
+            nickname: None,
+            distance,
+            yield_rate,
             hazards,
             kind: OrbitalKind::Planetoid,
             moons,
         }
     }
 
-    fn make_asteroid(&mut self, base_name: &str, idx: usize) -> OrbitalBody {
+    pub(crate) fn make_asteroid(&mut self, base_name: &str, idx: usize, path: &str) -> OrbitalBody {
         let name = format!("{} Belt {}", base_name, Self::roman_numeral(idx));
-        let hazards = self.hazards_for_body();
-        let nickname = self.maybe_nickname(&hazard_kinds(&hazards));
+        let hazards = self.hazards_for_body(path);
+        let (distance, yield_rate) = self.orbit(&OrbitalKind::AsteroidBelt, path);
+        let id = self.alloc_id();
+        self.roll_nickname(id, path, &hazard_kinds(&hazards));
 
         OrbitalBody {
-            id: self.alloc_id(),
+            id,
             name,
-            nickname,
-            distance: self.rng.gen_range(300..=900),
+            nickname: None,
+            distance,
+            yield_rate,
             hazards,
             kind: OrbitalKind::AsteroidBelt,
             moons: Vec::new(),
         }
     }
 
+    /// How many stars, planetoids and belts system `system_id` has.
+    pub(crate) fn layout(&self, system_id: u32) -> (usize, usize, usize) {
+        let mut layout = self.stream(&format!("system/{}/layout", system_id));
+        (
+            layout.gen_range(self.config.system.star_count.clone()),
+            layout.gen_range(self.config.system.planetoids.clone()),
+            layout.gen_range(self.config.system.asteroids.clone()),
+        )
+    }
+
     fn generate_system(&mut self, system_id: u32) -> StarSystem {
-        let star_count = self.rng_in_range(self.config.system.star_count.clone());
+        let path = format!("system/{}", system_id);
+        let (star_count, planetoid_count, asteroid_count) = self.layout(system_id);
+
         let mut stars = Vec::with_capacity(star_count);
-        for _ in 0..star_count {
-            stars.push(self.make_star());
+        for i in 0..star_count {
+            stars.push(self.make_star(system_id, &format!("{}/star/{}", path, i)));
         }
         let primary_name = stars
             .first()
             .map(|s| s.name.clone())
             .unwrap_or_else(|| "Unnamed".to_string());
 
-        let planetoid_count = self.rng_in_range(self.config.system.planetoids.clone());
         let mut orbitals = Vec::new();
         for i in 0..planetoid_count {
             let suffix = (b'b' + i as u8) as char;
-            orbitals.push(self.make_planetoid(&primary_name, suffix));
+            let body_path = format!("{}/planetoid/{}", path, i);
+            orbitals.push(self.make_planetoid(&primary_name, suffix, &body_path));
         }
 
-        let asteroid_count = self.rng_in_range(self.config.system.asteroids.clone());
         for i in 0..asteroid_count {
-            orbitals.push(self.make_asteroid(&primary_name, i));
+            let body_path = format!("{}/belt/{}", path, i);
+            orbitals.push(self.make_asteroid(&primary_name, i, &body_path));
         }
 
         StarSystem {
@@ -299,11 +493,73 @@ impl UniverseGenerator {
     }
 }
 
-pub fn universe_json(seed: u64) -> String {
+/// A generator of its own for whatever lives at `path` in a universe built
+/// from `seed`.
+fn stream(seed: u64, path: &str) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(Checksum::of(format!("{}/{}", seed, path).as_bytes()).0)
+}
+
+pub fn universe_json(seed: u64, viewer: Option<&Knowledge>) -> String {
     let mut gen = UniverseGenerator::new(seed);
     let universe = gen.generate();
-    let view = UniverseView::from(&universe);
-    serde_json::to_string(&view).unwrap_or_else(|_| "{}".to_string())
+    universe_view_json(&universe, viewer, Vec::new())
+}
+
+/// Serialises the universe as seen by `viewer`; `None` is the omniscient view.
+/// `fleets` should already be filtered down to what the viewer can see.
+pub fn universe_view_json(
+    universe: &Universe,
+    viewer: Option<&Knowledge>,
+    fleets: Vec<FleetView>,
+) -> String {
+    universe_view_json_with(universe, viewer, &TechModifiers::default(), fleets)
+}
+
+/// Like `universe_view_json`, with surveyed risks shown as a viewer with
+/// `mods` technology faces them.
+pub fn universe_view_json_with(
+    universe: &Universe,
+    viewer: Option<&Knowledge>,
+    mods: &TechModifiers,
+    fleets: Vec<FleetView>,
+) -> String {
+    serde_json::to_string(&universe_view(universe, viewer, mods, fleets))
+        .unwrap_or_else(|_| "{}".to_string())
+}
+
+/// What `universe_view_json_with` emits, for serialisers other than JSON.
+pub fn universe_view(
+    universe: &Universe,
+    viewer: Option<&Knowledge>,
+    mods: &TechModifiers,
+    fleets: Vec<FleetView>,
+) -> impl Serialize {
+    let mut view = UniverseView::seen_by(universe, viewer, mods);
+    view.fleets = fleets;
+    view
+}
+
+/// One system as it appears in the universe view, or `None` if `viewer`
+/// has never seen it.
+pub fn system_view(
+    universe: &Universe,
+    id: u32,
+    viewer: Option<&Knowledge>,
+    mods: &TechModifiers,
+) -> Option<impl Serialize> {
+    universe
+        .system(id)
+        .filter(|sys| viewer.is_none_or(|k| k.knows_system(sys.id)))
+        .map(|sys| SystemView::seen_by(sys, viewer, mods))
+}
+
+/// Bumped whenever the shape of the universe view changes, so the frontend
+/// can tell which one it is reading.
+pub const UNIVERSE_SCHEMA_VERSION: u32 = 2;
+
+/// JSON Schema for what `universe_view_json` emits.
+pub fn universe_schema() -> String {
+    serde_json::to_string_pretty(&schema_for!(UniverseView)).unwrap_or_else(|_| "{}".to_string())
 }
 
 fn kind_label(kind: &OrbitalKind) -> &'static str {
@@ -314,19 +570,20 @@ fn kind_label(kind: &OrbitalKind) -> &'static str {
     }
 }
 
-fn probe_failure(hazards: &[Hazard]) -> f64 {
-    let mut acc = RiskChannels::new();
-    for h in hazards {
-        apply_hazard(h, &mut acc);
-    }
-    acc.failure_prob(BASE_PROBE_FAILURE)
+pub fn probe_failure(hazards: &[Hazard]) -> f64 {
+    accumulate_risk(hazards).failure_prob(BASE_PROBE_FAILURE)
+}
+
+/// Probe failure chance for a player with the given technology.
+pub fn probe_failure_with(hazards: &[Hazard], modifiers: &TechModifiers) -> f64 {
+    accumulate_risk_with(hazards, modifiers).failure_prob(BASE_PROBE_FAILURE)
 }
 
 fn hazard_kinds(hazards: &[Hazard]) -> Vec<HazardKind> {
     hazards.iter().map(|h| h.kind).collect()
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 struct HazardView {
     kind: &'static str,
     probe_fail: f64,
@@ -334,34 +591,43 @@ struct HazardView {
     yield_penalty: f64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 struct OrbitalView {
+    id: u32,
     name: String,
     nickname: Option<String>,
     distance: u32,
     kind: &'static str,
-    probe_failure: f64,
-    hazards: Vec<HazardView>,
+    description: String,
+    intel: &'static str,
+    probe_failure: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    probe_failure_spread: Option<f64>,
+    yield_rate: Option<u32>,
+    hazards: Option<Vec<HazardView>>,
     moons: Vec<OrbitalView>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 struct StarView {
     name: String,
     nickname: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 struct SystemView {
     id: u32,
+    description: String,
     stars: Vec<StarView>,
     orbitals: Vec<OrbitalView>,
     links: Vec<u32>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 struct UniverseView {
+    schema_version: u32,
     systems: Vec<SystemView>,
+    fleets: Vec<FleetView>,
 }
 
 impl From<&Hazard> for HazardView {
@@ -375,16 +641,53 @@ impl From<&Hazard> for HazardView {
     }
 }
 
-impl From<&OrbitalBody> for OrbitalView {
-    fn from(body: &OrbitalBody) -> Self {
-        let moons = body.moons.iter().map(OrbitalView::from).collect();
-        let hazards: Vec<HazardView> = body.hazards.iter().map(HazardView::from).collect();
+impl OrbitalView {
+    fn seen_by(body: &OrbitalBody, viewer: Option<&Knowledge>, mods: &TechModifiers) -> Self {
+        let moons = body
+            .moons
+            .iter()
+            .map(|moon| OrbitalView::seen_by(moon, viewer, mods))
+            .collect();
+        let intel = match viewer {
+            None => Some(BodyIntel::Surveyed),
+            Some(knowledge) => knowledge.body_intel(body.id),
+        };
+        let surveyed = matches!(intel, Some(BodyIntel::Surveyed));
+        let seen_hazards = if surveyed {
+            hazard_kinds(&body.hazards)
+        } else {
+            Vec::new()
+        };
+        let (intel_label, probe_failure, spread, hazards) = match intel {
+            Some(BodyIntel::Surveyed) => (
+                "surveyed",
+                Some(probe_failure_with(&body.hazards, mods)),
+                None,
+                Some(
+                    mods.apply(&body.hazards)
+                        .iter()
+                        .map(HazardView::from)
+                        .collect(),
+                ),
+            ),
+            Some(BodyIntel::Estimated {
+                probe_failure,
+                spread,
+            }) => ("estimated", Some(probe_failure), Some(spread), None),
+            None => ("unknown", None, None, None),
+        };
+        let nickname = body.nickname.clone().filter(|_| surveyed);
         Self {
+            id: body.id,
             name: body.name.clone(),
-            nickname: body.nickname.clone(),
+            description: describe_body(body, &seen_hazards, nickname.as_deref()),
+            nickname,
             distance: body.distance,
             kind: kind_label(&body.kind),
-            probe_failure: probe_failure(&body.hazards),
+            intel: intel_label,
+            probe_failure,
+            probe_failure_spread: spread,
+            yield_rate: surveyed.then_some(body.yield_rate),
             hazards,
             moons,
         }
@@ -400,21 +703,143 @@ impl From<&Star> for StarView {
     }
 }
 
-impl From<&StarSystem> for SystemView {
-    fn from(system: &StarSystem) -> Self {
+impl SystemView {
+    fn seen_by(system: &StarSystem, viewer: Option<&Knowledge>, mods: &TechModifiers) -> Self {
         Self {
             id: system.id,
+            description: describe_system(system),
             stars: system.stars.iter().map(StarView::from).collect(),
-            orbitals: system.orbitals.iter().map(OrbitalView::from).collect(),
-            links: system.links.clone(),
+            orbitals: system
+                .orbitals
+                .iter()
+                .map(|body| OrbitalView::seen_by(body, viewer, mods))
+                .collect(),
+            links: system
+                .links
+                .iter()
+                .copied()
+                .filter(|id| viewer.is_none_or(|k| k.knows_system(*id)))
+                .collect(),
         }
     }
 }
 
-impl From<&Universe> for UniverseView {
-    fn from(universe: &Universe) -> Self {
+impl UniverseView {
+    fn seen_by(universe: &Universe, viewer: Option<&Knowledge>, mods: &TechModifiers) -> Self {
+        let systems = universe
+            .systems
+            .iter()
+            .filter(|sys| viewer.is_none_or(|k| k.knows_system(sys.id)))
+            .map(|sys| SystemView::seen_by(sys, viewer, mods))
+            .collect();
         Self {
-            systems: universe.systems.iter().map(SystemView::from).collect(),
+            schema_version: UNIVERSE_SCHEMA_VERSION,
+            systems,
+            fleets: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::hazard::HazardProfile;
+    use proptest::prelude::*;
+
+    fn finite() -> impl Strategy<Value = f64> {
+        prop::num::f64::NORMAL | prop::num::f64::SUBNORMAL | prop::num::f64::ZERO
+    }
+
+    fn hazard() -> impl Strategy<Value = Hazard> {
+        let kind = prop_oneof![
+            Just(HazardKind::Radiation),
+            Just(HazardKind::Pirates),
+            Just(HazardKind::Debris),
+        ];
+        (kind, finite(), finite(), finite()).prop_map(
+            |(kind, probe_fail, hull_damage, yield_penalty)| Hazard {
+                kind,
+                profile: HazardProfile {
+                    probe_fail,
+                    hull_damage,
+                    yield_penalty,
+                },
+            },
+        )
+    }
+
+    fn body(moons: BoxedStrategy<Vec<OrbitalBody>>) -> impl Strategy<Value = OrbitalBody> {
+        let kind = prop_oneof![
+            Just(OrbitalKind::Planetoid),
+            Just(OrbitalKind::AsteroidBelt),
+            Just(OrbitalKind::Moon),
+        ];
+        (
+            any::<u32>(),
+            "\\PC{0,16}",
+            proptest::option::of("\\PC{0,16}"),
+            any::<u32>(),
+            any::<u32>(),
+            prop::collection::vec(hazard(), 0..4),
+            kind,
+            moons,
+        )
+            .prop_map(
+                |(id, name, nickname, distance, yield_rate, hazards, kind, moons)| OrbitalBody {
+                    id,
+                    name,
+                    nickname,
+                    distance,
+                    yield_rate,
+                    hazards,
+                    kind,
+                    moons,
+                },
+            )
+    }
+
+    fn universe() -> impl Strategy<Value = Universe> {
+        let moon = body(Just(Vec::new()).boxed());
+        let orbital = body(prop::collection::vec(moon, 0..3).boxed());
+        let star = (
+            any::<u32>(),
+            "\\PC{0,16}",
+            proptest::option::of("\\PC{0,16}"),
+        )
+            .prop_map(|(id, name, nickname)| Star { id, name, nickname });
+        let system = (
+            any::<u32>(),
+            prop::collection::vec(star, 0..3),
+            prop::collection::vec(orbital, 0..4),
+            prop::collection::vec(any::<u32>(), 0..4),
+        )
+            .prop_map(|(id, stars, orbitals, links)| StarSystem {
+                id,
+                stars,
+                orbitals,
+                links,
+            });
+        prop::collection::vec(system, 0..4).prop_map(|systems| Universe { systems })
+    }
+
+    proptest! {
+        #[test]
+        fn any_universe_round_trips(universe in universe()) {
+            let json = serde_json::to_string(&universe).unwrap();
+            let back: Universe = serde_json::from_str(&json).unwrap();
+            prop_assert_eq!(back, universe);
+        }
+
+        #[test]
+        fn generated_universes_round_trip(seed in any::<u64>(), systems in 0usize..8) {
+            let config = UniverseConfig {
+                systems,
+                ..Default::default()
+            };
+            let universe = UniverseGenerator::with_config(seed, config).generate();
+            let json = serde_json::to_string_pretty(&universe).unwrap();
+            let back: Universe = serde_json::from_str(&json).unwrap();
+            prop_assert_eq!(back, universe);
         }
     }
 }
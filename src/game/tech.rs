@@ -0,0 +1,238 @@
+use crate::game::hazard::TechModifiers;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tech {
+    /// Hull plating that soaks some hazard damage.
+    Shielding,
+    /// Layered deflectors on top of shielding.
+    DeflectorArrays,
+    /// Probes that shrug off most radiation.
+    RadiationHardening,
+    /// Drills that keep working through hazards.
+    ImprovedDrills,
+    /// Sealed deep-core drills.
+    CoreDrills,
+}
+
+impl Tech {
+    pub const ALL: [Tech; 5] = [
+        Tech::Shielding,
+        Tech::DeflectorArrays,
+        Tech::RadiationHardening,
+        Tech::ImprovedDrills,
+        Tech::CoreDrills,
+    ];
+}
+
+pub struct TechInfo {
+    pub cost: f64,
+    pub requires: &'static [Tech],
+}
+
+pub fn tech_info(tech: Tech) -> TechInfo {
+    match tech {
+        Tech::Shielding => TechInfo {
+            cost: 20.0,
+            requires: &[],
+        },
+        Tech::DeflectorArrays => TechInfo {
+            cost: 40.0,
+            requires: &[Tech::Shielding],
+        },
+        Tech::RadiationHardening => TechInfo {
+            cost: 15.0,
+            requires: &[],
+        },
+        Tech::ImprovedDrills => TechInfo {
+            cost: 20.0,
+            requires: &[],
+        },
+        Tech::CoreDrills => TechInfo {
+            cost: 40.0,
+            requires: &[Tech::ImprovedDrills],
+        },
+    }
+}
+
+/// What one faction has researched.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Research {
+    unlocked: BTreeSet<Tech>,
+}
+
+impl Research {
+    pub fn has(&self, tech: Tech) -> bool {
+        self.unlocked.contains(&tech)
+    }
+
+    pub fn unlock(&mut self, tech: Tech) {
+        self.unlocked.insert(tech);
+    }
+
+    pub fn unlocked(&self) -> impl Iterator<Item = Tech> + '_ {
+        self.unlocked.iter().copied()
+    }
+
+    /// The first prerequisite of `tech` still missing, if any.
+    pub fn missing_prerequisite(&self, tech: Tech) -> Option<Tech> {
+        tech_info(tech)
+            .requires
+            .iter()
+            .copied()
+            .find(|t| !self.has(*t))
+    }
+
+    pub fn modifiers(&self) -> TechModifiers {
+        let mut mods = TechModifiers::default();
+        for tech in self.unlocked() {
+            match tech {
+                Tech::Shielding => mods.hull_damage *= 0.6,
+                Tech::DeflectorArrays => mods.hull_damage *= 0.5,
+                Tech::RadiationHardening => mods.radiation_probe_fail *= 0.3,
+                Tech::ImprovedDrills => mods.yield_penalty *= 0.5,
+                Tech::CoreDrills => mods.yield_penalty *= 0.5,
+            }
+        }
+        mods
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::economy::STARTING_CREDITS;
+    use crate::game::hazard::{accumulate_risk_with, hazard_profile, Hazard, HazardKind};
+    use crate::game::order::{Order, OrderRejection};
+    use crate::game::probe::run_trials_with;
+    use crate::game::report::ReportEntry;
+    use crate::game::system::{probe_failure_with, OrbitalBody, OrbitalKind, BASE_PROBE_FAILURE};
+    use crate::game::Game;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn hazard(kind: HazardKind) -> Hazard {
+        Hazard {
+            kind,
+            profile: hazard_profile(kind),
+        }
+    }
+
+    #[test]
+    fn unlocks_scale_their_own_channels() {
+        let hazards = [hazard(HazardKind::Radiation), hazard(HazardKind::Pirates)];
+        let mut research = Research::default();
+        let base = accumulate_risk_with(&hazards, &research.modifiers());
+
+        research.unlock(Tech::RadiationHardening);
+        let hardened = accumulate_risk_with(&hazards, &research.modifiers());
+        assert_eq!(hardened.additive, base.additive);
+        assert_eq!(hardened.multiplier, base.multiplier);
+        let expected = (1.0 - 0.1 * 0.3) * (1.0 - 0.4);
+        assert!((hardened.noisy_or_survival - expected).abs() < 1e-12);
+
+        research.unlock(Tech::Shielding);
+        research.unlock(Tech::ImprovedDrills);
+        let mods = research.modifiers();
+        let all = accumulate_risk_with(&hazards, &mods);
+        assert!((all.additive - 0.5 * 0.6).abs() < 1e-12);
+        let expected = (1.0 - 0.4 * 0.5) * (1.0 - 0.1 * 0.5);
+        assert!((all.multiplier - expected).abs() < 1e-12);
+        assert!(
+            probe_failure_with(&hazards, &mods)
+                < probe_failure_with(&hazards, &TechModifiers::default())
+        );
+        assert_eq!(
+            all.failure_prob(BASE_PROBE_FAILURE),
+            probe_failure_with(&hazards, &mods)
+        );
+    }
+
+    #[test]
+    fn prerequisites_gate_the_tree() {
+        let mut research = Research::default();
+        assert_eq!(
+            research.missing_prerequisite(Tech::DeflectorArrays),
+            Some(Tech::Shielding)
+        );
+        research.unlock(Tech::Shielding);
+        assert_eq!(research.missing_prerequisite(Tech::DeflectorArrays), None);
+        for tech in Tech::ALL {
+            assert!(tech_info(tech).requires.iter().all(|r| *r != tech));
+        }
+    }
+
+    #[test]
+    fn research_orders_unlock_techs_for_credits() {
+        let mut game = Game::new(12);
+        assert_eq!(
+            game.submit(
+                0,
+                Order::Research {
+                    tech: Tech::CoreDrills
+                }
+            ),
+            Err(OrderRejection::MissingPrerequisite {
+                tech: Tech::CoreDrills,
+                requires: Tech::ImprovedDrills,
+            })
+        );
+        game.submit(
+            0,
+            Order::Research {
+                tech: Tech::ImprovedDrills,
+            },
+        )
+        .unwrap();
+        let report = game.tick();
+        assert!(report.entries.contains(&ReportEntry::TechResearched {
+            player: 0,
+            tech: Tech::ImprovedDrills,
+        }));
+        let player = &game.players()[0];
+        assert!(player.research.has(Tech::ImprovedDrills));
+        assert_eq!(
+            player.stockpile.credits,
+            STARTING_CREDITS - tech_info(Tech::ImprovedDrills).cost
+        );
+        assert_eq!(
+            game.submit(
+                0,
+                Order::Research {
+                    tech: Tech::ImprovedDrills
+                }
+            ),
+            Err(OrderRejection::AlreadyResearched {
+                tech: Tech::ImprovedDrills
+            })
+        );
+    }
+
+    #[test]
+    fn trials_match_the_adjusted_analytic_failure() {
+        let body = OrbitalBody {
+            id: 1,
+            name: "Trial b".to_string(),
+            nickname: None,
+            distance: 40,
+            yield_rate: 3,
+            hazards: vec![hazard(HazardKind::Radiation), hazard(HazardKind::Debris)],
+            kind: OrbitalKind::Planetoid,
+            moons: Vec::new(),
+        };
+        let mut research = Research::default();
+        research.unlock(Tech::RadiationHardening);
+        research.unlock(Tech::Shielding);
+        let mods = research.modifiers();
+
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let summary = run_trials_with(&mut rng, &body, 20_000, &mods);
+        assert_eq!(
+            summary.analytic_failure,
+            probe_failure_with(&body.hazards, &mods)
+        );
+        assert!((summary.empirical_failure() - summary.analytic_failure).abs() < 0.01);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::game::player::PlayerId;
+use crate::game::Game;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// One way to win. Every condition in the game's `VictoryConfig` is checked
+/// at the end of each tick; the first player to meet any of them wins.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VictoryCondition {
+    /// Survey at least this fraction of all bodies.
+    Survey { fraction: f64 },
+    /// Hold every colony in at least this many systems.
+    Control { systems: usize },
+    /// Stockpile this many credits.
+    Wealth { credits: f64 },
+    /// Still have a fleet or colony when this turn ends the game.
+    Survive { turns: u32 },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VictoryConfig {
+    pub conditions: Vec<VictoryCondition>,
+}
+
+impl Default for VictoryConfig {
+    fn default() -> Self {
+        Self {
+            conditions: vec![
+                VictoryCondition::Survey { fraction: 0.8 },
+                VictoryCondition::Control { systems: 4 },
+                VictoryCondition::Wealth { credits: 1000.0 },
+                VictoryCondition::Survive { turns: 300 },
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Victory {
+    pub player: PlayerId,
+    pub condition: VictoryCondition,
+    pub turn: u32,
+}
+
+const SURVEY_POINTS: f64 = 2.0;
+const SYSTEM_POINTS: f64 = 1.0;
+const COLONY_POINTS: f64 = 5.0;
+const CONTROL_POINTS: f64 = 10.0;
+const CREDITS_PER_POINT: f64 = 10.0;
+const SHIP_POINTS: f64 = 2.0;
+
+/// Points by category; `total` breaks ties between simultaneous winners.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Score {
+    pub exploration: f64,
+    pub territory: f64,
+    pub economy: f64,
+    pub military: f64,
+    pub total: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConditionProgress {
+    #[serde(flatten)]
+    pub condition: VictoryCondition,
+    pub current: f64,
+    pub target: f64,
+    pub met: bool,
+}
+
+/// Systems where `player` owns at least one colony and nobody else owns any.
+pub fn controlled_systems(game: &Game, player: PlayerId) -> usize {
+    let mut mine = BTreeSet::new();
+    let mut contested = BTreeSet::new();
+    for colony in game.colonies() {
+        let Some((system, _)) = game.universe().locate_body(colony.body) else {
+            continue;
+        };
+        if colony.owner == player {
+            mine.insert(system.id);
+        } else {
+            contested.insert(system.id);
+        }
+    }
+    mine.difference(&contested).count()
+}
+
+/// Whether `player` still has anything in play.
+pub fn standing(game: &Game, player: PlayerId) -> bool {
+    game.fleets().iter().any(|f| f.owner == player)
+        || game.colonies().iter().any(|c| c.owner == player)
+}
+
+pub fn score(game: &Game, player: PlayerId) -> Score {
+    let Some(p) = game.player(player) else {
+        return Score {
+            exploration: 0.0,
+            territory: 0.0,
+            economy: 0.0,
+            military: 0.0,
+            total: 0.0,
+        };
+    };
+    let colonies = game.colonies().iter().filter(|c| c.owner == player).count();
+    let ships: usize = game
+        .fleets()
+        .iter()
+        .filter(|f| f.owner == player)
+        .map(|f| f.ships.len())
+        .sum();
+
+    let exploration = p.knowledge.surveyed_bodies() as f64 * SURVEY_POINTS
+        + p.knowledge.known_systems() as f64 * SYSTEM_POINTS;
+    let territory =
+        colonies as f64 * COLONY_POINTS + controlled_systems(game, player) as f64 * CONTROL_POINTS;
+    let economy = (p.stockpile.credits / CREDITS_PER_POINT).floor();
+    let military = ships as f64 * SHIP_POINTS;
+    Score {
+        exploration,
+        territory,
+        economy,
+        military,
+        total: exploration + territory + economy + military,
+    }
+}
+
+pub fn progress(game: &Game, player: PlayerId, condition: &VictoryCondition) -> ConditionProgress {
+    let Some(p) = game.player(player) else {
+        return ConditionProgress {
+            condition: condition.clone(),
+            current: 0.0,
+            target: 0.0,
+            met: false,
+        };
+    };
+    let (current, target) = match condition {
+        VictoryCondition::Survey { fraction } => {
+            let total = game.universe().bodies().count().max(1);
+            (
+                p.knowledge.surveyed_bodies() as f64 / total as f64,
+                *fraction,
+            )
+        }
+        VictoryCondition::Control { systems } => {
+            (controlled_systems(game, player) as f64, *systems as f64)
+        }
+        VictoryCondition::Wealth { credits } => (p.stockpile.credits, *credits),
+        VictoryCondition::Survive { turns } => (game.turn() as f64, *turns as f64),
+    };
+    let met = current >= target
+        && match condition {
+            VictoryCondition::Survive { .. } => standing(game, player),
+            _ => true,
+        };
+    ConditionProgress {
+        condition: condition.clone(),
+        current,
+        target,
+        met,
+    }
+}
+
+/// Who has won as of now, if anyone. When several players get there on the
+/// same tick the higher score takes it, then the lower player id.
+pub fn evaluate(game: &Game, config: &VictoryConfig) -> Option<Victory> {
+    game.players()
+        .iter()
+        .filter_map(|p| {
+            let condition = config
+                .conditions
+                .iter()
+                .find(|c| progress(game, p.id, c).met)?;
+            Some((p.id, condition, score(game, p.id).total))
+        })
+        .max_by(|a, b| a.2.total_cmp(&b.2).then(b.0.cmp(&a.0)))
+        .map(|(player, condition, _)| Victory {
+            player,
+            condition: condition.clone(),
+            turn: game.turn(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::order::{Order, OrderRejection};
+    use crate::game::tests::colonising_home_body;
+
+    #[test]
+    fn scores_break_down_by_category() {
+        let (mut game, _) = colonising_home_body(12);
+        game.tick();
+
+        let score = score(&game, 0);
+        assert_eq!(score.exploration, SURVEY_POINTS + SYSTEM_POINTS);
+        assert_eq!(score.territory, COLONY_POINTS + CONTROL_POINTS);
+        assert_eq!(score.economy, 1.0);
+        assert_eq!(score.military, SHIP_POINTS);
+        assert_eq!(
+            score.total,
+            score.exploration + score.territory + score.economy + score.military
+        );
+    }
+
+    #[test]
+    fn first_condition_met_ends_the_game() {
+        let config = VictoryConfig {
+            conditions: vec![
+                VictoryCondition::Wealth { credits: 1000.0 },
+                VictoryCondition::Survive { turns: 3 },
+            ],
+        };
+        let mut game = Game::with_victory(5, 2, config.clone());
+        let wealth = progress(&game, 1, &config.conditions[0]);
+        assert_eq!(
+            (wealth.current, wealth.target, wealth.met),
+            (25.0, 1000.0, false)
+        );
+
+        game.tick();
+        game.tick();
+        assert_eq!(game.outcome(), None);
+        game.tick();
+        let victory = game.outcome().cloned().expect("both factions survive");
+        assert_eq!(victory.turn, 3);
+        assert_eq!(victory.condition, VictoryCondition::Survive { turns: 3 });
+        let winner = score(&game, victory.player).total;
+        assert!(game
+            .players()
+            .iter()
+            .all(|p| score(&game, p.id).total <= winner));
+
+        assert_eq!(
+            game.submit(
+                0,
+                Order::SetStance {
+                    toward: 1,
+                    stance: crate::game::faction::Stance::War,
+                },
+            ),
+            Err(OrderRejection::GameOver)
+        );
+        game.tick();
+        assert_eq!(game.outcome(), Some(&victory));
+    }
+}
@@ -1,6 +1,7 @@
-use serde::Serialize;
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CubeCoord {
     pub x: i32,
     pub y: i32,
@@ -31,11 +32,11 @@ fn zigzag(v: i32) -> u32 {
     ((v << 1) ^ (v >> 31)) as u32
 }
 
-fn pack_id(q: i32, r: i32) -> u64 {
+pub fn pack_id(q: i32, r: i32) -> u64 {
     ((zigzag(q) as u64) << 32) | zigzag(r) as u64
 }
 
-fn unpack_id(id: u64) -> (i32, i32) {
+pub fn unpack_id(id: u64) -> (i32, i32) {
     fn unzigzag(v: u32) -> i32 {
         ((v >> 1) as i32) ^ -((v & 1) as i32)
     }
@@ -44,12 +45,16 @@ fn unpack_id(id: u64) -> (i32, i32) {
     (q, r)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct HexCell {
     id: u64,
     coord: CubeCoord,
 }
 
+/// Saved as just its centre and radius; the cells are rebuilt on load, so
+/// a hand-edited grid can't end up with cells that don't fit its shape.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "GridShape", into = "GridShape")]
 pub struct HexGrid {
     radius: u32,
     center_q: i32,
@@ -57,6 +62,29 @@ pub struct HexGrid {
     cells: Vec<HexCell>,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+struct GridShape {
+    radius: u32,
+    center_q: i32,
+    center_r: i32,
+}
+
+impl From<GridShape> for HexGrid {
+    fn from(shape: GridShape) -> Self {
+        Self::window(shape.center_q, shape.center_r, shape.radius)
+    }
+}
+
+impl From<HexGrid> for GridShape {
+    fn from(grid: HexGrid) -> Self {
+        Self {
+            radius: grid.radius,
+            center_q: grid.center_q,
+            center_r: grid.center_r,
+        }
+    }
+}
+
 impl HexGrid {
     pub fn new(radius: u32) -> Self {
         Self::window(0, 0, radius)
@@ -120,6 +148,7 @@ impl HexGrid {
             .collect();
 
         HexGridView {
+            schema_version: HEX_GRID_SCHEMA_VERSION,
             radius: self.radius,
             diameter: self.diameter(),
             cell_count: self.cell_count(),
@@ -130,7 +159,7 @@ impl HexGrid {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct HexCellView {
     pub id: String,
     pub key: String,
@@ -142,8 +171,9 @@ pub struct HexCellView {
     pub distance: u32,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct HexGridView {
+    pub schema_version: u32,
     pub radius: u32,
     pub diameter: u32,
     pub cell_count: usize,
@@ -152,6 +182,14 @@ pub struct HexGridView {
     pub cells: Vec<HexCellView>,
 }
 
+/// Bumped whenever the shape of `HexGridView` changes.
+pub const HEX_GRID_SCHEMA_VERSION: u32 = 1;
+
+/// JSON Schema for what `grid_json` and `window_json` emit.
+pub fn hex_grid_schema() -> String {
+    serde_json::to_string_pretty(&schema_for!(HexGridView)).unwrap_or_else(|_| "{}".to_string())
+}
+
 pub fn grid_json(radius: u32) -> String {
     let grid = HexGrid::new(radius);
     let view = grid.view();
@@ -167,6 +205,24 @@ pub fn window_json(center_q: i32, center_r: i32, radius: u32) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn grids_round_trip(radius in 0u32..8, q in -1000i32..1000, r in -1000i32..1000) {
+            let grid = HexGrid::window(q, r, radius);
+            let json = serde_json::to_string(&grid).unwrap();
+            let back: HexGrid = serde_json::from_str(&json).unwrap();
+            prop_assert_eq!(back, grid);
+        }
+
+        #[test]
+        fn coords_round_trip(x in -1000i32..1000, z in -1000i32..1000) {
+            let coord = CubeCoord::new(x, -x - z, z);
+            let json = serde_json::to_string(&coord).unwrap();
+            prop_assert_eq!(serde_json::from_str::<CubeCoord>(&json).unwrap(), coord);
+        }
+    }
 
     #[test]
     fn hex_grid_counts_match_formula() {
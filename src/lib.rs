@@ -1,13 +1,15 @@
+use serde::Serialize;
 use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
 
-mod game;
-mod hex;
+pub mod game;
+pub mod hex;
+use game::replay::SnapshotView;
 use game::system::universe_json;
 use game::Game;
 
 thread_local! {
-    static GAME: RefCell<Option<Game>> = RefCell::new(None);
+    static GAME: RefCell<Option<Game>> = const { RefCell::new(None) };
 }
 
 fn with_game_mut<R>(f: impl FnOnce(&mut Game) -> R) -> Result<R, &'static str> {
@@ -27,17 +29,181 @@ pub fn init_game(seed: u64) {
     });
 }
 
+#[wasm_bindgen]
+pub fn init_game_with_factions(seed: u64, factions: u32) {
+    GAME.with(|g| {
+        *g.borrow_mut() = Some(Game::with_factions(seed, factions as usize));
+    });
+}
+
+/// Starts a game played to the victory conditions in `config`, shaped like
+/// `{"conditions": [{"type": "survey", "fraction": 0.5}, ...]}`. Answers
+/// with `{"started": bool, "error": string | null}`.
+#[wasm_bindgen]
+pub fn init_game_with_victory(seed: u64, factions: u32, config: &str) -> String {
+    let error = match serde_json::from_str::<game::victory::VictoryConfig>(config) {
+        Ok(victory) => {
+            GAME.with(|g| {
+                *g.borrow_mut() = Some(Game::with_victory(seed, factions as usize, victory));
+            });
+            None
+        }
+        Err(e) => Some(e.to_string()),
+    };
+    serde_json::json!({ "started": error.is_none(), "error": error }).to_string()
+}
+
+/// Starts a game for `factions` players on a hand-authored universe, with
+/// anything the file leaves out generated from `seed`. Answers with
+/// `{"loaded": bool, "error": string | null}`; a rejected file keeps the
+/// current game.
+#[wasm_bindgen]
+pub fn load_universe(json: &str, seed: u64, factions: u32) -> String {
+    let error = match game::scenario::load_scenario(json, seed, factions as usize) {
+        Ok(loaded) => {
+            GAME.with(|g| *g.borrow_mut() = Some(loaded));
+            None
+        }
+        Err(e) => Some(e.to_string()),
+    };
+    serde_json::json!({ "loaded": error.is_none(), "error": error }).to_string()
+}
+
 #[wasm_bindgen]
 pub fn tick() -> String {
-    match with_game_mut(|game| game.tick().to_string()) {
+    match with_game_mut(|game| {
+        serde_json::to_string(&game.tick()).unwrap_or_else(|_| "{}".to_string())
+    }) {
         Ok(v) => v,
         Err(e) => e.to_string(),
     }
 }
 
 #[wasm_bindgen]
-pub fn greeting() -> String {
-    match with_game_mut(|game| game::greeting_for(game)) {
+pub fn submit_order(json: &str) -> String {
+    match with_game_mut(|game| game::order::submit_json(game, json)) {
+        Ok(v) => v,
+        Err(e) => e.to_string(),
+    }
+}
+
+/// The running game's universe as `viewer` sees it, fleets included; pass no
+/// viewer for the omniscient view.
+#[wasm_bindgen]
+pub fn game_universe(viewer: Option<u32>) -> String {
+    match with_game_mut(|game| game.universe_json(viewer)) {
+        Ok(v) => v,
+        Err(e) => e.to_string(),
+    }
+}
+
+#[wasm_bindgen]
+pub fn save_game() -> String {
+    match with_game_mut(|game| game::save::save_game(game)) {
+        Ok(v) => v,
+        Err(e) => e.to_string(),
+    }
+}
+
+/// Replaces the running game with a saved one. Answers with
+/// `{"loaded": bool, "error": string | null}`; a failed load keeps the
+/// current game.
+#[wasm_bindgen]
+pub fn load_game(data: &str) -> String {
+    let error = match game::save::load_game(data) {
+        Ok(loaded) => {
+            GAME.with(|g| *g.borrow_mut() = Some(loaded));
+            None
+        }
+        Err(e) => Some(e.to_string()),
+    };
+    serde_json::json!({ "loaded": error.is_none(), "error": error }).to_string()
+}
+
+/// Narrated events from `turn`, or from the latest turn when none is given,
+/// as `{"turn": number, "events": [...]}`. Pass a viewer to hear only what
+/// `game_status` would tell them.
+#[wasm_bindgen]
+pub fn turn_log(turn: Option<u32>, viewer: Option<u32>) -> String {
+    match with_game_mut(|game| {
+        game::event::turn_log_json(game.events(), turn.unwrap_or(game.turn()), viewer)
+    }) {
+        Ok(v) => v,
+        Err(e) => e.to_string(),
+    }
+}
+
+/// Hex fingerprint of the running game's state.
+#[wasm_bindgen]
+pub fn game_checksum() -> String {
+    match with_game_mut(|game| game.checksum().to_string()) {
+        Ok(v) => v,
+        Err(e) => e.to_string(),
+    }
+}
+
+/// The running game's seed and every order resolved so far, as JSON.
+#[wasm_bindgen]
+pub fn order_log() -> String {
+    match with_game_mut(|game| serde_json::to_string(&game.order_log()).unwrap_or_default()) {
+        Ok(v) => v,
+        Err(e) => e.to_string(),
+    }
+}
+
+/// Rebuilds a game from an order log as of `turn` and makes it the running
+/// game. Answers with `{"replayed": bool, "checksum": string | null,
+/// "error": string | null}`; a failed replay keeps the current game.
+#[wasm_bindgen]
+pub fn replay_game(log: &str, turn: u32) -> String {
+    let (checksum, error) = match game::replay::replay_json(log, turn) {
+        Ok(replayed) => {
+            let checksum = replayed.checksum().to_string();
+            GAME.with(|g| *g.borrow_mut() = Some(replayed));
+            (Some(checksum), None)
+        }
+        Err(e) => (None, Some(e.to_string())),
+    };
+    serde_json::json!({ "replayed": error.is_none(), "checksum": checksum, "error": error })
+        .to_string()
+}
+
+/// Steps the running game back to the end of `turn`; the next tick branches
+/// from there. Answers with `{"rewound": bool, "turn": number,
+/// "error": string | null}`.
+#[wasm_bindgen]
+pub fn rewind(turn: u32) -> String {
+    match with_game_mut(|game| {
+        let error = game.rewind(turn).err().map(|e| e.to_string());
+        serde_json::json!({ "rewound": error.is_none(), "turn": game.turn(), "error": error })
+            .to_string()
+    }) {
+        Ok(v) => v,
+        Err(e) => e.to_string(),
+    }
+}
+
+/// The turns the running game holds snapshots for, as
+/// `[{"turn": number, "checksum": string}]`. Rewinds to other turns replay
+/// from the nearest earlier one.
+#[wasm_bindgen]
+pub fn list_snapshots() -> String {
+    match with_game_mut(|game| {
+        let snapshots: Vec<SnapshotView> =
+            game.snapshots().iter().map(SnapshotView::from).collect();
+        serde_json::to_string(&snapshots).unwrap_or_else(|_| "[]".to_string())
+    }) {
+        Ok(v) => v,
+        Err(e) => e.to_string(),
+    }
+}
+
+/// Everything the HUD needs in one call: turn, resources, fleet summaries,
+/// the latest events and victory progress. Pass a viewer to see only their
+/// own seat.
+#[wasm_bindgen]
+pub fn game_status(viewer: Option<u32>) -> String {
+    match with_game_mut(|game| game::status::status_json(game, viewer)) {
         Ok(v) => v,
         Err(e) => e.to_string(),
     }
@@ -45,7 +211,7 @@ pub fn greeting() -> String {
 
 #[wasm_bindgen]
 pub fn generate_universe(seed: u64) -> String {
-    universe_json(seed)
+    universe_json(seed, None)
 }
 
 #[wasm_bindgen]
@@ -57,3 +223,122 @@ pub fn hex_grid(radius: u32) -> String {
 pub fn hex_window(center_q: i32, center_r: i32, radius: u32) -> String {
     hex::window_json(center_q, center_r, radius)
 }
+
+/// The typed exports below hand over the same shapes as their JSON
+/// namesakes as plain JS objects, so the frontend skips `JSON.parse`.
+/// Missing values come through as `null`, as they do in the JSON.
+fn to_js(value: &impl Serialize) -> Result<JsValue, JsError> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// `generate_universe` as an object.
+#[wasm_bindgen]
+pub fn generate_universe_object(seed: u64) -> Result<JsValue, JsError> {
+    to_js(&generated_universe_view(seed))
+}
+
+fn generated_universe_view(seed: u64) -> impl Serialize {
+    let universe = game::system::UniverseGenerator::new(seed).generate();
+    game::system::universe_view(&universe, None, &Default::default(), Vec::new())
+}
+
+/// `game_universe` as an object; throws if no game is running.
+#[wasm_bindgen]
+pub fn game_universe_object(viewer: Option<u32>) -> Result<JsValue, JsError> {
+    with_game_mut(|game| to_js(&game.universe_view(viewer))).map_err(JsError::new)?
+}
+
+/// One system of the running game as `viewer` sees it, or `null` if they
+/// have not found it.
+#[wasm_bindgen]
+pub fn game_system_object(id: u32, viewer: Option<u32>) -> Result<JsValue, JsError> {
+    with_game_mut(|game| to_js(&game.system_view(id, viewer))).map_err(JsError::new)?
+}
+
+/// `hex_window` as an object.
+#[wasm_bindgen]
+pub fn hex_window_object(center_q: i32, center_r: i32, radius: u32) -> Result<JsValue, JsError> {
+    to_js(&hex::HexGrid::window(center_q, center_r, radius).view())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::system::{universe_schema, UNIVERSE_SCHEMA_VERSION};
+    use hex::{hex_grid_schema, HEX_GRID_SCHEMA_VERSION};
+    use std::path::PathBuf;
+
+    /// Schemas are pinned in `www/schema/<name>.v<version>.json`. Changing a
+    /// view means bumping its version and running the tests once with
+    /// `UPDATE_SCHEMAS=1` to pin the new one; pinned files are never
+    /// rewritten.
+    fn assert_schema_pinned(name: &str, version: u32, schema: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("www/schema")
+            .join(format!("{}.v{}.json", name, version));
+        match std::fs::read_to_string(&path) {
+            Ok(pinned) => assert_eq!(
+                pinned.trim_end(),
+                schema,
+                "the {} schema changed without a version bump",
+                name
+            ),
+            Err(_) if std::env::var_os("UPDATE_SCHEMAS").is_some() => {
+                std::fs::write(&path, format!("{}\n", schema)).expect("schema written");
+            }
+            Err(_) => panic!(
+                "{} is not pinned; run the tests with UPDATE_SCHEMAS=1",
+                path.display()
+            ),
+        }
+    }
+
+    fn parsed(json: &str) -> serde_json::Value {
+        serde_json::from_str(json).expect("valid JSON")
+    }
+
+    fn typed(view: &impl Serialize) -> serde_json::Value {
+        serde_json::to_value(view).expect("serialisable view")
+    }
+
+    /// The `*_object` exports only run under wasm, so this checks the views
+    /// they hand to `to_js` against their JSON namesakes natively.
+    #[test]
+    fn typed_exports_carry_the_same_views_as_the_json_ones() {
+        assert_eq!(
+            typed(&generated_universe_view(3)),
+            parsed(&generate_universe(3))
+        );
+        assert_eq!(
+            typed(&hex::HexGrid::window(2, -1, 3).view()),
+            parsed(&hex_window(2, -1, 3))
+        );
+
+        let game = game::Game::with_factions(11, 2);
+        for viewer in [None, Some(0), Some(1), Some(7)] {
+            let universe = parsed(&game.universe_json(viewer));
+            assert_eq!(typed(&game.universe_view(viewer)), universe);
+            for system in universe["systems"].as_array().unwrap() {
+                let id = system["id"].as_u64().unwrap() as u32;
+                assert_eq!(typed(&game.system_view(id, viewer)), *system);
+            }
+        }
+        assert_eq!(
+            typed(&game.system_view(u32::MAX, None)),
+            serde_json::Value::Null
+        );
+    }
+
+    #[test]
+    fn view_schemas_only_change_with_their_version() {
+        assert_schema_pinned("universe", UNIVERSE_SCHEMA_VERSION, &universe_schema());
+        assert_schema_pinned("hex_grid", HEX_GRID_SCHEMA_VERSION, &hex_grid_schema());
+
+        let universe: serde_json::Value = serde_json::from_str(&generate_universe(3)).unwrap();
+        assert_eq!(universe["schema_version"], UNIVERSE_SCHEMA_VERSION);
+        let grid: serde_json::Value = serde_json::from_str(&hex_grid(2)).unwrap();
+        assert_eq!(grid["schema_version"], HEX_GRID_SCHEMA_VERSION);
+    }
+}
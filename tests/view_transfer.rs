@@ -0,0 +1,58 @@
+//! Times the JSON universe export against the typed one on a large
+//! universe: JSON pays for `serde_json` on the way out and `JSON.parse` on
+//! the way in, the typed export builds the JS objects directly. Run with
+//! `wasm-pack test --node --release -- --test view_transfer --nocapture`.
+#![cfg(target_arch = "wasm32")]
+
+use more_space::game::hazard::TechModifiers;
+use more_space::game::system::{
+    universe_view, universe_view_json, Universe, UniverseConfig, UniverseGenerator,
+};
+use serde::Serialize;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+const RUNS: u32 = 10;
+
+fn mean_ms(mut f: impl FnMut() -> JsValue) -> (f64, JsValue) {
+    let mut last = JsValue::NULL;
+    let start = js_sys::Date::now();
+    for _ in 0..RUNS {
+        last = f();
+    }
+    ((js_sys::Date::now() - start) / RUNS as f64, last)
+}
+
+fn large_universe(systems: usize) -> Universe {
+    let config = UniverseConfig {
+        systems,
+        ..Default::default()
+    };
+    UniverseGenerator::with_config(7, config).generate()
+}
+
+#[wasm_bindgen_test]
+fn typed_universes_match_parsed_json() {
+    for systems in [100, 1000, 4000] {
+        let universe = large_universe(systems);
+        let (json_ms, parsed) = mean_ms(|| {
+            let json = universe_view_json(&universe, None, Vec::new());
+            js_sys::JSON::parse(&json).unwrap()
+        });
+        let (typed_ms, typed) = mean_ms(|| {
+            universe_view(&universe, None, &TechModifiers::default(), Vec::new())
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .unwrap()
+        });
+        assert_eq!(
+            js_sys::JSON::stringify(&typed).unwrap(),
+            js_sys::JSON::stringify(&parsed).unwrap()
+        );
+        console_log!(
+            "{} systems: JSON + parse {:.1} ms, typed {:.1} ms",
+            systems,
+            json_ms,
+            typed_ms
+        );
+    }
+}
@@ -22,7 +22,7 @@ import {
 } from "@babylonjs/core";
 import { CreateGreasedLine } from "@babylonjs/core/Meshes/Builders/greasedLineBuilder";
 import "@babylonjs/inspector";
-import initWasm, { hex_window } from "../pkg/more_space.js";
+import initWasm, { hex_window_object, HexCellView } from "../pkg/more_space.js";
 import {
     bodyStyle,
     createNebula,
@@ -34,25 +34,10 @@ import {
     PlanetMesh,
 } from "./planet-helpers";
 
-type HexCell = {
-    id: string; // canonical packed id from Rust
-    key: string;
-    x: number;
-    y: number;
-    z: number;
-    q: number;
-    r: number;
-    distance: number;
-};
+type HexCell = HexCellView;
 
-type HexGrid = {
-    radius: number;
-    diameter: number;
-    cell_count: number;
-    center_q: number;
-    center_r: number;
-    cells: HexCell[];
-};
+// Matches HEX_GRID_SCHEMA_VERSION in src/hex.rs; see www/schema/.
+const HEX_GRID_SCHEMA_VERSION = 1;
 
 const canvas = document.getElementById("hexCanvas") as unknown as HTMLCanvasElement;
 const radiusInput = document.getElementById("radiusInput") as HTMLInputElement;
@@ -265,8 +250,7 @@ function describeCell(cell: HexCell): string {
 
 function roundtripStatus(cell: HexCell): string {
     try {
-        const clicked = clickedCells.map(({ q, r }) => [q, r] as [number, number]);
-        const res = JSON.parse(hex_window(cell.q, cell.r, 0, clicked)) as HexGrid;
+        const res = hex_window_object(cell.q, cell.r, 0);
         const found = res.cells.find((c) => c.q === cell.q && c.r === cell.r);
         if (!found) return "roundtrip: missing";
         return found.id === cell.id ? "roundtrip: ok" : `roundtrip: mismatch wasm=${found.id}`;
@@ -724,8 +708,10 @@ function renderPlanets(s: Scene): void {
 }
 
 function renderGrid(centerQ: number, centerR: number, radius: number, s: Scene): void {
-    const json = hex_window(centerQ, centerR, radius);
-    const grid = JSON.parse(json) as HexGrid;
+    const grid = hex_window_object(centerQ, centerR, radius);
+    if (grid.schema_version !== HEX_GRID_SCHEMA_VERSION) {
+        console.warn(`hex grid schema v${grid.schema_version}, expected v${HEX_GRID_SCHEMA_VERSION}`);
+    }
 
     const polyPoints: Vector3[][] = [];
     const centerWorld = axialToWorld(centerQ, centerR);
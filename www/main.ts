@@ -17,7 +17,7 @@ import {
     TransformNode,
     Vector3,
 } from "@babylonjs/core";
-import initWasm, { generate_universe } from "../pkg/more_space.js";
+import initWasm, { generate_universe_object } from "../pkg/more_space.js";
 import {
     bodyStyle,
     createOrbitLine,
@@ -37,6 +37,8 @@ const VIEW_HEIGHT = 1400;
 const PIXEL_TO_WORLD = 0.14;
 const NODE_RADIUS = 22;
 const ORBIT_BASE = 22;
+// Matches UNIVERSE_SCHEMA_VERSION in src/game/system.rs; see www/schema/.
+const UNIVERSE_SCHEMA_VERSION = 2;
 const MIN_CAMERA_RADIUS = 45;
 const MAX_CAMERA_RADIUS = 320;
 
@@ -346,8 +348,10 @@ function setupPointerHandling(s: Scene): void {
 
 async function renderUniverse(seed: bigint): Promise<void> {
     try {
-        const json = generate_universe(seed);
-        universeData = JSON.parse(json);
+        universeData = generate_universe_object(seed);
+        if (universeData.schema_version !== UNIVERSE_SCHEMA_VERSION) {
+            console.warn(`universe schema v${universeData.schema_version}, expected v${UNIVERSE_SCHEMA_VERSION}`);
+        }
         buildScene(universeData);
     } catch (err) {
         console.error(err);
@@ -361,6 +365,7 @@ function showSystemInfo(sys: any, evt: MouseEvent): void {
     showTooltip(
         `
     <strong>${systemLabel(sys)}</strong><br/>
+    <em>${sys.description}</em><br/>
     Stars: ${stars}<br/>
     Links: ${links}<br/>
     Orbits: ${sys.orbitals.length}
@@ -374,6 +379,7 @@ function showOrbitInfo(sys: any, orb: any, evt: MouseEvent): void {
     showTooltip(
         `
     <strong>${orb.name}${orb.nickname ? ` (${orb.nickname})` : ""}</strong><br/>
+    <em>${orb.description}</em><br/>
     System: ${systemLabel(sys)}<br/>
     Kind: ${orb.kind}<br/>
     Distance: ${orb.distance}<br/>
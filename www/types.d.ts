@@ -1,6 +1,110 @@
 declare module "../pkg/more_space.js" {
+  // Shapes of the `*_object` exports; they follow www/schema/universe.v2.json
+  // and www/schema/hex_grid.v1.json, and change only with those versions.
+  export type HullClass = "scout" | "frigate" | "freighter";
+
+  export interface StarView {
+    name: string;
+    nickname?: string | null;
+  }
+
+  export interface HazardView {
+    kind: string;
+    probe_fail: number;
+    hull_damage: number;
+    yield_penalty: number;
+  }
+
+  export interface OrbitalView {
+    id: number;
+    name: string;
+    nickname?: string | null;
+    kind: string;
+    distance: number;
+    description: string;
+    intel: string;
+    yield_rate?: number | null;
+    probe_failure?: number | null;
+    probe_failure_spread?: number | null;
+    hazards?: HazardView[] | null;
+    moons: OrbitalView[];
+  }
+
+  export interface SystemView {
+    id: number;
+    description: string;
+    stars: StarView[];
+    orbitals: OrbitalView[];
+    links: number[];
+  }
+
+  export interface ShipView {
+    hull: HullClass;
+    integrity: number;
+    armour: number;
+  }
+
+  export interface FleetView {
+    id: number;
+    owner: number;
+    system: number;
+    ships: ShipView[];
+    probes: number;
+    route: number[];
+    progress: number;
+    destination?: number | null;
+    eta?: number | null;
+  }
+
+  export interface UniverseView {
+    schema_version: number;
+    systems: SystemView[];
+    fleets: FleetView[];
+  }
+
+  export interface HexCellView {
+    id: string; // canonical packed id from Rust
+    key: string;
+    x: number;
+    y: number;
+    z: number;
+    q: number;
+    r: number;
+    distance: number;
+  }
+
+  export interface HexGridView {
+    schema_version: number;
+    radius: number;
+    diameter: number;
+    cell_count: number;
+    center_q: number;
+    center_r: number;
+    cells: HexCellView[];
+  }
+
   export default function init(): Promise<void>;
   export function generate_universe(seed: bigint): string;
+  export function init_game(seed: bigint): void;
+  export function init_game_with_factions(seed: bigint, factions: number): void;
+  export function init_game_with_victory(seed: bigint, factions: number, config: string): string;
+  export function load_universe(json: string, seed: bigint, factions: number): string;
+  export function tick(): string;
+  export function submit_order(json: string): string;
+  export function game_universe(viewer?: number | null): string;
+  export function game_status(viewer?: number | null): string;
+  export function turn_log(turn?: number | null, viewer?: number | null): string;
+  export function save_game(): string;
+  export function load_game(data: string): string;
+  export function game_checksum(): string;
+  export function order_log(): string;
+  export function replay_game(log: string, turn: number): string;
+  export function rewind(turn: number): string;
+  export function list_snapshots(): string;
   export function hex_grid(radius: number): string;
   export function hex_window(center_q: number, center_r: number, radius: number): string;
+  export function generate_universe_object(seed: bigint): UniverseView;
+  export function game_universe_object(viewer?: number | null): UniverseView;
+  export function game_system_object(id: number, viewer?: number | null): SystemView | null;
+  export function hex_window_object(center_q: number, center_r: number, radius: number): HexGridView;
 }